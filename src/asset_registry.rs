@@ -0,0 +1,389 @@
+use std::collections::HashMap;
+
+use ethers::types::H128;
+
+use crate::{
+    Error,
+    errors::Result,
+    info::client::InfoClient,
+    meta::{AssetMeta, Meta, SpotAssetMeta, SpotMeta, TokenInfo},
+};
+
+/// Spot asset ids are offset by this value on the wire.
+pub(crate) const SPOT_ASSET_OFFSET: u32 = 10_000;
+/// Asset ids of builder-deployed perp dexes start at this value on the wire.
+const PERP_DEX_ASSET_OFFSET: u32 = 100_000;
+/// Each builder-deployed perp dex gets this many asset ids.
+const PERP_DEX_ASSET_RANGE: u32 = 10_000;
+const PERP_MAX_DECIMALS: u32 = 6;
+const SPOT_MAX_DECIMALS: u32 = 8;
+/// USDC is always token 0 on Hyperliquid.
+const USDC_TOKEN_INDEX: usize = 0;
+
+/// Typed asset identifier.
+///
+/// `Perp` holds the index into the perp universe, `Spot` holds the index into the
+/// spot universe and `PerpDex` the dex id and index into the universe of a builder-deployed
+/// perp dex. Use [`AssetId::to_wire`] to get the id expected by the exchange endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AssetId {
+    Perp(u32),
+    Spot(u32),
+    PerpDex { dex: u32, index: u32 },
+}
+
+impl AssetId {
+    pub fn to_wire(self) -> u32 {
+        match self {
+            AssetId::Perp(index) => index,
+            AssetId::Spot(index) => SPOT_ASSET_OFFSET + index,
+            AssetId::PerpDex { dex, index } => {
+                PERP_DEX_ASSET_OFFSET + dex * PERP_DEX_ASSET_RANGE + index
+            }
+        }
+    }
+
+    pub fn is_spot(&self) -> bool {
+        matches!(self, AssetId::Spot(_))
+    }
+}
+
+impl From<AssetId> for u32 {
+    fn from(asset: AssetId) -> Self {
+        asset.to_wire()
+    }
+}
+
+/// Registry of every tradable asset, built from the perp and spot metadata.
+///
+/// Coins can be looked up by perp name (`ETH`), spot pair name (`PURR/USDC`),
+/// spot universe alias (`@107`) or the token id of the base token of a USDC pair.
+/// Perps of builder-deployed dexes added with [`AssetRegistry::add_perp_dex`] are
+/// looked up as `dex:COIN`.
+#[derive(Debug, Clone)]
+pub struct AssetRegistry {
+    perps: Vec<AssetMeta>,
+    perp_dexs: HashMap<u32, PerpDexUniverse>,
+    spots: HashMap<u32, SpotAssetMeta>,
+    tokens: HashMap<usize, TokenInfo>,
+    coin_to_asset: HashMap<String, AssetId>,
+    token_id_to_index: HashMap<H128, usize>,
+}
+
+impl AssetRegistry {
+    pub fn new(meta: Meta, spot_meta: SpotMeta) -> Self {
+        let mut coin_to_asset = HashMap::new();
+        for (index, asset) in meta.universe.iter().enumerate() {
+            coin_to_asset.insert(asset.name.clone(), AssetId::Perp(index as u32));
+        }
+
+        let tokens: HashMap<usize, TokenInfo> = spot_meta
+            .tokens
+            .into_iter()
+            .map(|token| (token.index, token))
+            .collect();
+        let token_id_to_index = tokens
+            .values()
+            .map(|token| (token.token_id, token.index))
+            .collect();
+
+        let mut spots = HashMap::new();
+        for asset in spot_meta.universe {
+            let id = AssetId::Spot(asset.index as u32);
+            coin_to_asset.insert(asset.name.clone(), id);
+            coin_to_asset.insert(format!("@{}", asset.index), id);
+
+            if let (Some(base), Some(quote)) =
+                (tokens.get(&asset.tokens[0]), tokens.get(&asset.tokens[1]))
+            {
+                coin_to_asset.insert(format!("{}/{}", base.name, quote.name), id);
+                if quote.index == USDC_TOKEN_INDEX {
+                    coin_to_asset
+                        .entry(format!("{:#x}", base.token_id))
+                        .or_insert(id);
+                }
+            }
+
+            spots.insert(asset.index as u32, asset);
+        }
+
+        Self {
+            perps: meta.universe,
+            perp_dexs: HashMap::new(),
+            spots,
+            tokens,
+            coin_to_asset,
+            token_id_to_index,
+        }
+    }
+
+    /// Fetches the perp and spot metadata and builds a registry from it.
+    pub async fn fetch(info_client: &InfoClient) -> Result<Self> {
+        let meta = info_client.meta().await?;
+        let spot_meta = info_client.spot_meta().await?;
+
+        Ok(Self::new(meta, spot_meta))
+    }
+
+    /// Registers the perps of a builder-deployed perp dex with id `dex`.
+    pub fn add_perp_dex(&mut self, dex: u32, name: &str, meta: Meta) {
+        for (index, asset) in meta.universe.iter().enumerate() {
+            let id = AssetId::PerpDex {
+                dex,
+                index: index as u32,
+            };
+            if asset.name.contains(':') {
+                self.coin_to_asset.insert(asset.name.clone(), id);
+            } else {
+                self.coin_to_asset
+                    .insert(format!("{name}:{}", asset.name), id);
+            }
+        }
+
+        self.perp_dexs.insert(
+            dex,
+            PerpDexUniverse {
+                name: name.to_string(),
+                perps: meta.universe,
+            },
+        );
+    }
+
+    /// Looks up the perp dex called `name` and registers its perps.
+    pub async fn fetch_perp_dex(&mut self, info_client: &InfoClient, name: &str) -> Result<()> {
+        let dex = info_client
+            .perp_dexs()
+            .await?
+            .iter()
+            .position(|dex| dex.as_ref().is_some_and(|dex| dex.name == name))
+            .ok_or_else(|| Error::PerpDexNotFound(name.to_string()))?;
+        let meta = info_client.perp_dex_meta(name).await?;
+
+        self.add_perp_dex(dex as u32, name, meta);
+        Ok(())
+    }
+
+    /// Name of the perp dex of the asset, `None` for the default dex and spot assets.
+    pub fn perp_dex_name(&self, asset: AssetId) -> Option<&str> {
+        match asset {
+            AssetId::PerpDex { dex, .. } => self.perp_dexs.get(&dex).map(|dex| dex.name.as_str()),
+            _ => None,
+        }
+    }
+
+    /// Names of the registered builder-deployed perp dexes.
+    pub fn perp_dex_names(&self) -> impl Iterator<Item = &str> {
+        self.perp_dexs.values().map(|dex| dex.name.as_str())
+    }
+
+    /// Resolves a coin name, pair name, `@index` alias or token id to an [`AssetId`].
+    pub fn asset(&self, coin: &str) -> Result<AssetId> {
+        if let Some(asset) = self.coin_to_asset.get(coin) {
+            return Ok(*asset);
+        }
+
+        coin.parse::<H128>()
+            .ok()
+            .and_then(|token_id| self.coin_to_asset.get(&format!("{token_id:#x}")))
+            .copied()
+            .ok_or(Error::AssetNotFound)
+    }
+
+    /// Name used for the asset by the info endpoints, e.g. `ETH`, `PURR/USDC` or `@107`.
+    pub fn name(&self, asset: AssetId) -> Result<&str> {
+        match asset {
+            AssetId::Perp(index) => self.perp_meta(index).map(|meta| meta.name.as_str()),
+            AssetId::Spot(index) => self.spot_meta(index).map(|meta| meta.name.as_str()),
+            AssetId::PerpDex { dex, index } => self
+                .perp_dex_meta(dex, index)
+                .map(|meta| meta.name.as_str()),
+        }
+    }
+
+    pub fn perp_meta(&self, index: u32) -> Result<&AssetMeta> {
+        self.perps.get(index as usize).ok_or(Error::AssetNotFound)
+    }
+
+    pub fn perp_dex_meta(&self, dex: u32, index: u32) -> Result<&AssetMeta> {
+        self.perp_dexs
+            .get(&dex)
+            .and_then(|dex| dex.perps.get(index as usize))
+            .ok_or(Error::AssetNotFound)
+    }
+
+    pub fn spot_meta(&self, index: u32) -> Result<&SpotAssetMeta> {
+        self.spots.get(&index).ok_or(Error::AssetNotFound)
+    }
+
+    /// Metadata of a perp asset, from the default universe or a builder-deployed dex.
+    pub fn asset_meta(&self, asset: AssetId) -> Result<&AssetMeta> {
+        match asset {
+            AssetId::Perp(index) => self.perp_meta(index),
+            AssetId::PerpDex { dex, index } => self.perp_dex_meta(dex, index),
+            AssetId::Spot(_) => Err(Error::AssetNotFound),
+        }
+    }
+
+    /// Size decimals of the asset. For spot assets these are the base token's.
+    pub fn sz_decimals(&self, asset: AssetId) -> Result<u32> {
+        match asset {
+            AssetId::Perp(index) => self.perp_meta(index).map(|meta| meta.sz_decimals),
+            AssetId::PerpDex { dex, index } => {
+                self.perp_dex_meta(dex, index).map(|meta| meta.sz_decimals)
+            }
+            AssetId::Spot(_) => {
+                let (base, _) = self.spot_tokens(asset)?;
+                Ok(base.sz_decimals as u32)
+            }
+        }
+    }
+
+    /// Maximum number of decimals allowed in a price for the asset.
+    pub fn price_decimals(&self, asset: AssetId) -> Result<u32> {
+        let max_decimals = if asset.is_spot() {
+            SPOT_MAX_DECIMALS
+        } else {
+            PERP_MAX_DECIMALS
+        };
+
+        Ok(max_decimals.saturating_sub(self.sz_decimals(asset)?))
+    }
+
+    /// Base and quote token of a spot asset.
+    pub fn spot_tokens(&self, asset: AssetId) -> Result<(&TokenInfo, &TokenInfo)> {
+        let AssetId::Spot(index) = asset else {
+            return Err(Error::AssetNotFound);
+        };
+        let meta = self.spot_meta(index)?;
+        let base = self
+            .tokens
+            .get(&meta.tokens[0])
+            .ok_or(Error::TokenNotFound)?;
+        let quote = self
+            .tokens
+            .get(&meta.tokens[1])
+            .ok_or(Error::TokenNotFound)?;
+
+        Ok((base, quote))
+    }
+
+    /// Looks up a token by name (`PURR`) or token id.
+    pub fn token(&self, token: &str) -> Result<&TokenInfo> {
+        if let Some(info) = self.tokens.values().find(|info| info.name == token) {
+            return Ok(info);
+        }
+
+        token
+            .parse::<H128>()
+            .ok()
+            .and_then(|token_id| self.token_id_to_index.get(&token_id))
+            .and_then(|index| self.tokens.get(index))
+            .ok_or(Error::TokenNotFound)
+    }
+
+    pub fn perps(&self) -> impl Iterator<Item = (AssetId, &AssetMeta)> {
+        self.perps
+            .iter()
+            .enumerate()
+            .map(|(index, meta)| (AssetId::Perp(index as u32), meta))
+    }
+
+    pub fn spots(&self) -> impl Iterator<Item = (AssetId, &SpotAssetMeta)> {
+        self.spots
+            .iter()
+            .map(|(index, meta)| (AssetId::Spot(*index), meta))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PerpDexUniverse {
+    name: String,
+    perps: Vec<AssetMeta>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry() -> AssetRegistry {
+        let meta: Meta = serde_json::from_str(
+            r#"{"universe":[{"name":"BTC","szDecimals":5,"maxLeverage":40},{"name":"ETH","szDecimals":4,"maxLeverage":25}]}"#,
+        )
+        .unwrap();
+        let spot_meta: SpotMeta = serde_json::from_str(
+            r#"{
+                "universe":[
+                    {"tokens":[1,0],"name":"PURR/USDC","index":0,"isCanonical":true},
+                    {"tokens":[2,0],"name":"@1","index":1,"isCanonical":false}
+                ],
+                "tokens":[
+                    {"name":"USDC","szDecimals":8,"weiDecimals":8,"index":0,"tokenId":"0x6d1e7cde53ba9467b783cb7c530ce054","isCanonical":true},
+                    {"name":"PURR","szDecimals":0,"weiDecimals":5,"index":1,"tokenId":"0xc1fb593aeffbeb02f85e0308e9956a90","isCanonical":true},
+                    {"name":"HFUN","szDecimals":2,"weiDecimals":8,"index":2,"tokenId":"0xbaf265ef389da684513d98d68edf4eae","isCanonical":false}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        AssetRegistry::new(meta, spot_meta)
+    }
+
+    #[test]
+    fn asset_lookup_test() -> Result<()> {
+        let registry = registry();
+
+        assert_eq!(registry.asset("ETH")?, AssetId::Perp(1));
+        assert_eq!(registry.asset("PURR/USDC")?, AssetId::Spot(0));
+        assert_eq!(registry.asset("@0")?, AssetId::Spot(0));
+        assert_eq!(registry.asset("HFUN/USDC")?, AssetId::Spot(1));
+        assert_eq!(registry.asset("@1")?, AssetId::Spot(1));
+        assert_eq!(
+            registry.asset("0xbaf265ef389da684513d98d68edf4eae")?,
+            AssetId::Spot(1)
+        );
+        assert!(registry.asset("DOGE").is_err());
+
+        assert_eq!(registry.asset("ETH")?.to_wire(), 1);
+        assert_eq!(registry.asset("HFUN/USDC")?.to_wire(), 10_001);
+        assert_eq!(registry.name(AssetId::Spot(1))?, "@1");
+        Ok(())
+    }
+
+    #[test]
+    fn perp_dex_lookup_test() -> Result<()> {
+        let mut registry = registry();
+        let meta: Meta = serde_json::from_str(
+            r#"{"universe":[{"name":"test:ABC","szDecimals":0,"maxLeverage":10},{"name":"test:XYZ","szDecimals":2,"maxLeverage":10}]}"#,
+        )
+        .unwrap();
+        registry.add_perp_dex(1, "test", meta);
+
+        let asset = registry.asset("test:XYZ")?;
+        assert_eq!(asset, AssetId::PerpDex { dex: 1, index: 1 });
+        assert_eq!(asset.to_wire(), 110_001);
+        assert_eq!(registry.sz_decimals(asset)?, 2);
+        assert_eq!(registry.price_decimals(asset)?, 4);
+        assert_eq!(registry.perp_dex_name(asset), Some("test"));
+        assert_eq!(registry.asset("ETH")?, AssetId::Perp(1));
+        Ok(())
+    }
+
+    #[test]
+    fn asset_decimals_test() -> Result<()> {
+        let registry = registry();
+
+        assert_eq!(registry.sz_decimals(AssetId::Perp(0))?, 5);
+        assert_eq!(registry.price_decimals(AssetId::Perp(0))?, 1);
+        assert_eq!(registry.sz_decimals(AssetId::Spot(1))?, 2);
+        assert_eq!(registry.price_decimals(AssetId::Spot(1))?, 6);
+
+        let (base, quote) = registry.spot_tokens(AssetId::Spot(0))?;
+        assert_eq!((base.name.as_str(), quote.name.as_str()), ("PURR", "USDC"));
+        assert_eq!(registry.token("HFUN")?.index, 2);
+        assert_eq!(
+            registry.token("0xc1fb593aeffbeb02f85e0308e9956a90")?.name,
+            "PURR"
+        );
+        Ok(())
+    }
+}
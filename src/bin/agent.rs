@@ -40,12 +40,8 @@ async fn main() {
 
     tracing::info!("Agent address: {:?}", wallet.address());
 
-    let exchange_client = ExchangeClient::builder()
-        .wallet(wallet)
-        .network(NetworkType::Testnet)
-        .build()
-        .await
-        .unwrap();
+    // Orders are signed by the agent but placed for the master account
+    let exchange_client = exchange_client.with_agent(wallet);
 
     let order = ClientOrderRequest {
         asset: "ETH".to_string(),
@@ -0,0 +1,45 @@
+use ethers::signers::LocalWallet;
+use tracing::info;
+
+use hyperliquid_sdk::{CancelFilter, ExchangeClient, NetworkType};
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+#[tokio::main]
+async fn main() {
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::fmt::layer()
+                .event_format(tracing_subscriber::fmt::format().compact())
+                .with_timer(tracing_subscriber::fmt::time::LocalTime::rfc_3339()),
+        )
+        .with(
+            tracing_subscriber::EnvFilter::builder()
+                .with_default_directive(tracing::Level::DEBUG.into())
+                .from_env_lossy(),
+        )
+        .init();
+    // Key was randomly generated for testing and shouldn't be used with any real funds
+    let wallet: LocalWallet = "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+        .parse()
+        .unwrap();
+
+    let exchange_client = ExchangeClient::builder()
+        .wallet(wallet)
+        .network(NetworkType::Testnet)
+        .build()
+        .await
+        .unwrap();
+
+    let cancelled = exchange_client.cancel_all(CancelFilter::All).await.unwrap();
+    for order in cancelled {
+        info!("cancel {} {}: {:?}", order.coin, order.oid, order.status);
+    }
+
+    let closed = exchange_client.flatten_all().await.unwrap();
+    for position in closed {
+        info!(
+            "close {} {}: {:?}",
+            position.coin, position.szi, position.status
+        );
+    }
+}
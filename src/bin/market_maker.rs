@@ -2,9 +2,9 @@ use ethers::signers::LocalWallet;
 /*
 This is an example of a basic market making strategy.
 
-We subscribe to the current mid price and build a market around this price. Whenever our market becomes outdated, we place and cancel orders to renew it.
+We subscribe to the current mid price and build a market around this price. Whenever our market becomes outdated, we modify, place and cancel orders to renew it.
 */
-use hyperliquid_sdk::{MarketMaker, MarketMakerInput};
+use hyperliquid_sdk::{MarketMaker, MarketMakerInput, NetworkType};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 #[tokio::main]
@@ -34,6 +34,11 @@ async fn main() {
         max_absolute_position_size: 0.5,
         decimals: 1,
         wallet,
+        network: NetworkType::Testnet,
     };
-    MarketMaker::new(market_maker_input).await.start().await
+    let mut market_maker = MarketMaker::new(market_maker_input).await.unwrap();
+    // Runs until Ctrl+C, then cancels the resting quotes
+    if let Err(e) = market_maker.start().await {
+        tracing::error!("Market maker stopped: {e}");
+    }
 }
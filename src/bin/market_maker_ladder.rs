@@ -0,0 +1,48 @@
+use ethers::signers::LocalWallet;
+/*
+This is an example of a market making strategy quoting several levels per side.
+
+Prices are taken from the microprice of the book and skewed against our position, so the side
+reducing it is quoted tighter as the position grows.
+*/
+use hyperliquid_sdk::{MarketMaker, NetworkType, ReferencePrice, SpreadStrategy};
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+#[tokio::main]
+async fn main() {
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::fmt::layer()
+                .event_format(tracing_subscriber::fmt::format().compact())
+                .with_timer(tracing_subscriber::fmt::time::LocalTime::rfc_3339()),
+        )
+        .with(
+            tracing_subscriber::EnvFilter::builder()
+                .with_default_directive(tracing::Level::DEBUG.into())
+                .from_env_lossy(),
+        )
+        .init();
+    // Key was randomly generated for testing and shouldn't be used with any real funds
+    let wallet: LocalWallet =
+        "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+            .parse()
+            .unwrap();
+    let strategy = SpreadStrategy {
+        ladder: vec![0.05, 0.1, 0.2],
+        level_spacing: 3,
+        inventory_skew: 5,
+        reference: ReferencePrice::Microprice,
+        ..SpreadStrategy::new(0.05, 2, 0.5, 1)
+    };
+    let mut market_maker = MarketMaker::builder("ETH", strategy)
+        .network(NetworkType::Testnet)
+        .wallet(wallet)
+        .max_bps_diff(2)
+        .build()
+        .await
+        .unwrap();
+    // Runs until Ctrl+C, then cancels the resting quotes
+    if let Err(e) = market_maker.start().await {
+        tracing::error!("Market maker stopped: {e}");
+    }
+}
@@ -1,8 +1,7 @@
 use ethers::signers::LocalWallet;
 
 use hyperliquid_sdk::{
-    ExchangeClient, ExchangeDataStatus, ExchangeResponseStatus, MarketCloseParams,
-    MarketOrderParams, NetworkType,
+    ExchangeClient, ExchangeDataStatus, MarketCloseParams, MarketOrderParams, NetworkType,
 };
 use std::{thread::sleep, time::Duration};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
@@ -51,11 +50,7 @@ async fn main() {
         .unwrap();
     tracing::info!("Market open order placed: {response:?}");
 
-    let response = match response {
-        ExchangeResponseStatus::Ok(exchange_response) => exchange_response,
-        ExchangeResponseStatus::Err(e) => panic!("Error with exchange response: {e}"),
-    };
-    let status = response.data.unwrap().statuses[0].clone();
+    let status = response.into_status().unwrap();
     match status {
         ExchangeDataStatus::Filled(order) => tracing::info!("Order filled: {order:?}"),
         ExchangeDataStatus::Resting(order) => tracing::info!("Order resting: {order:?}"),
@@ -81,11 +76,7 @@ async fn main() {
         .unwrap();
     tracing::info!("Market close order placed: {response:?}");
 
-    let response = match response {
-        ExchangeResponseStatus::Ok(exchange_response) => exchange_response,
-        ExchangeResponseStatus::Err(e) => panic!("Error with exchange response: {e}"),
-    };
-    let status = response.data.unwrap().statuses[0].clone();
+    let status = response.into_status().unwrap();
     match status {
         ExchangeDataStatus::Filled(order) => tracing::info!("Close order filled: {order:?}"),
         ExchangeDataStatus::Resting(order) => tracing::info!("Close order resting: {order:?}"),
@@ -1,8 +1,8 @@
 use ethers::signers::LocalWallet;
 
 use hyperliquid_sdk::{
-    BuilderInfo, ExchangeClient, ExchangeDataStatus, ExchangeResponseStatus, MarketCloseParams,
-    MarketOrderParams, NetworkType,
+    BuilderInfo, ExchangeClient, ExchangeDataStatus, MarketCloseParams, MarketOrderParams,
+    NetworkType,
 };
 use std::{thread::sleep, time::Duration};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
@@ -60,11 +60,7 @@ async fn main() {
         .unwrap();
     tracing::info!("Market open order placed: {response:?}");
 
-    let response = match response {
-        ExchangeResponseStatus::Ok(exchange_response) => exchange_response,
-        ExchangeResponseStatus::Err(e) => panic!("Error with exchange response: {e}"),
-    };
-    let status = response.data.unwrap().statuses[0].clone();
+    let status = response.into_status().unwrap();
     match status {
         ExchangeDataStatus::Filled(order) => tracing::info!("Order filled: {order:?}"),
         ExchangeDataStatus::Resting(order) => tracing::info!("Order resting: {order:?}"),
@@ -90,11 +86,7 @@ async fn main() {
         .unwrap();
     tracing::info!("Market close order placed: {response:?}");
 
-    let response = match response {
-        ExchangeResponseStatus::Ok(exchange_response) => exchange_response,
-        ExchangeResponseStatus::Err(e) => panic!("Error with exchange response: {e}"),
-    };
-    let status = response.data.unwrap().statuses[0].clone();
+    let status = response.into_status().unwrap();
     match status {
         ExchangeDataStatus::Filled(order) => tracing::info!("Close order filled: {order:?}"),
         ExchangeDataStatus::Resting(order) => tracing::info!("Close order resting: {order:?}"),
@@ -2,7 +2,7 @@ use ethers::signers::LocalWallet;
 
 use hyperliquid_sdk::{
     ClientCancelRequest, ClientLimit, ClientOrder, ClientOrderRequest, ExchangeClient,
-    ExchangeDataStatus, ExchangeResponseStatus, LimitTif, NetworkType,
+    ExchangeDataStatus, LimitTif, NetworkType,
 };
 use std::{thread::sleep, time::Duration};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
@@ -47,11 +47,7 @@ async fn main() {
     let response = exchange_client.order(order).await.unwrap();
     tracing::info!("Order placed: {response:?}");
 
-    let response = match response {
-        ExchangeResponseStatus::Ok(exchange_response) => exchange_response,
-        ExchangeResponseStatus::Err(e) => panic!("error with exchange response: {e}"),
-    };
-    let status = response.data.unwrap().statuses[0].clone();
+    let status = response.into_status().unwrap();
     let oid = match status {
         ExchangeDataStatus::Filled(order) => order.oid,
         ExchangeDataStatus::Resting(order) => order.oid,
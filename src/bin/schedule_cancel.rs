@@ -0,0 +1,41 @@
+use ethers::signers::LocalWallet;
+
+use hyperliquid_sdk::{ExchangeClient, NetworkType, shutdown_signal};
+use std::time::Duration;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+#[tokio::main]
+async fn main() {
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::fmt::layer()
+                .event_format(tracing_subscriber::fmt::format().compact())
+                .with_timer(tracing_subscriber::fmt::time::LocalTime::rfc_3339()),
+        )
+        .with(
+            tracing_subscriber::EnvFilter::builder()
+                .with_default_directive(tracing::Level::DEBUG.into())
+                .from_env_lossy(),
+        )
+        .init();
+    // Key was randomly generated for testing and shouldn't be used with any real funds
+    let wallet: LocalWallet =
+        "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+            .parse()
+            .unwrap();
+
+    let exchange_client = ExchangeClient::builder()
+        .wallet(wallet)
+        .network(NetworkType::Testnet)
+        .build()
+        .await
+        .unwrap();
+
+    // Open orders get cancelled if no heartbeat makes it through for 60 seconds
+    let heartbeat = exchange_client
+        .schedule_cancel_heartbeat(Duration::from_secs(15), Duration::from_secs(60))
+        .unwrap();
+
+    shutdown_signal().await;
+    heartbeat.stop().await;
+}
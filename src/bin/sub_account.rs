@@ -0,0 +1,54 @@
+use ethers::signers::{LocalWallet, Signer};
+
+use hyperliquid_sdk::{ExchangeClient, InfoClient, NetworkType};
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+#[tokio::main]
+async fn main() {
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::fmt::layer()
+                .event_format(tracing_subscriber::fmt::format().compact())
+                .with_timer(tracing_subscriber::fmt::time::LocalTime::rfc_3339()),
+        )
+        .with(
+            tracing_subscriber::EnvFilter::builder()
+                .with_default_directive(tracing::Level::DEBUG.into())
+                .from_env_lossy(),
+        )
+        .init();
+    // Key was randomly generated for testing and shouldn't be used with any real funds
+    let wallet: LocalWallet =
+        "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+            .parse()
+            .unwrap();
+    let user = wallet.address();
+
+    let exchange_client = ExchangeClient::builder()
+        .wallet(wallet)
+        .network(NetworkType::Testnet)
+        .build()
+        .await
+        .unwrap();
+    let info_client = InfoClient::builder().network(NetworkType::Testnet).build();
+
+    let response = exchange_client
+        .create_sub_account("example".to_string())
+        .await
+        .unwrap();
+    tracing::info!("Sub-account created: {response:?}");
+    let sub_account = response.sub_account_user;
+
+    // 1 USDC
+    let response = exchange_client
+        .sub_account_transfer(sub_account, true, 1_000_000)
+        .await
+        .unwrap();
+    tracing::info!("USDC transferred to sub-account: {response:?}");
+
+    let sub_accounts = info_client.sub_accounts(user).await.unwrap();
+    tracing::info!("Sub-accounts: {sub_accounts:?}");
+
+    // Orders placed with this client are executed for the sub-account
+    let _sub_account_client = exchange_client.for_sub_account(sub_account);
+}
@@ -0,0 +1,58 @@
+use ethers::signers::{LocalWallet, Signer};
+
+use hyperliquid_sdk::{ClientTwapRequest, ExchangeClient, InfoClient, NetworkType};
+use std::{thread::sleep, time::Duration};
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+#[tokio::main]
+async fn main() {
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::fmt::layer()
+                .event_format(tracing_subscriber::fmt::format().compact())
+                .with_timer(tracing_subscriber::fmt::time::LocalTime::rfc_3339()),
+        )
+        .with(
+            tracing_subscriber::EnvFilter::builder()
+                .with_default_directive(tracing::Level::DEBUG.into())
+                .from_env_lossy(),
+        )
+        .init();
+    // Key was randomly generated for testing and shouldn't be used with any real funds
+    let wallet: LocalWallet =
+        "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+            .parse()
+            .unwrap();
+    let user = wallet.address();
+
+    let exchange_client = ExchangeClient::builder()
+        .wallet(wallet)
+        .network(NetworkType::Testnet)
+        .build()
+        .await
+        .unwrap();
+    let info_client = InfoClient::builder().network(NetworkType::Testnet).build();
+
+    let twap = ClientTwapRequest {
+        asset: "ETH".to_string(),
+        is_buy: true,
+        sz: 0.1,
+        reduce_only: false,
+        minutes: 10,
+        randomize: false,
+    };
+
+    let response = exchange_client.twap_order(twap).await.unwrap();
+    tracing::info!("TWAP placed: {response:?}");
+
+    let twap_id = response.into_twap_id().unwrap();
+
+    // Give the TWAP time to execute a slice or two
+    sleep(Duration::from_secs(60));
+
+    let fills = info_client.user_twap_slice_fills(user).await.unwrap();
+    tracing::info!("TWAP slice fills: {fills:?}");
+
+    let response = exchange_client.twap_cancel("ETH", twap_id).await.unwrap();
+    tracing::info!("TWAP cancelled: {response:?}");
+}
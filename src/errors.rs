@@ -23,6 +23,10 @@ pub enum Error {
     ChainNotAllowed,
     #[error("Asset not found")]
     AssetNotFound,
+    #[error("Token not found")]
+    TokenNotFound,
+    #[error("Perp dex not found: {0}")]
+    PerpDexNotFound(String),
     #[error("Error from Eip712 struct: {0:?}")]
     Eip712(String),
     #[error("Json parse error: {0:?}")]
@@ -61,6 +65,8 @@ pub enum Error {
     FloatStringParse,
     #[error("No cloid found in order request when expected")]
     NoCloid,
+    #[error("Invalid cloid, expected a uuid or 0x followed by 32 hex digits: {0}")]
+    InvalidCloid(String),
     #[error("ECDSA signature failed: {0:?}")]
     SignatureFailure(String),
     #[error("Vault address not found")]
@@ -77,4 +83,28 @@ pub enum Error {
     InvalidTriggerTpsl(String),
     #[error("Invalid limit tif: {0}")]
     InvalidLimitTif(String),
+    #[error("Invalid tpsl grouping: {0}")]
+    InvalidTpslGrouping(String),
+    #[error("Exchange error: {0}")]
+    Exchange(String),
+    #[error("Invalid schedule cancel heartbeat: {0}")]
+    InvalidScheduleCancel(String),
+    #[error("Agent key file error: {0}")]
+    AgentKeyFile(String),
+    #[error("Builder fee error: {0}")]
+    BuilderFee(String),
+    #[error("Leverage {leverage} for {coin} is outside of 1 to {max_leverage}")]
+    InvalidLeverage {
+        coin: String,
+        leverage: u32,
+        max_leverage: u32,
+    },
+    #[error("{0} only supports isolated margin")]
+    OnlyIsolated(String),
+    #[error("Invalid isolated margin amount: {0}")]
+    InvalidMarginAmount(f64),
+    #[error("Order size {sz} for {coin} is above the maximum tradable size {max_sz}")]
+    OrderSizeExceeded { coin: String, sz: f64, max_sz: f64 },
+    #[error("Risk limit exceeded: {0}")]
+    RiskLimit(crate::RiskViolation),
 }
@@ -1,6 +1,6 @@
 use crate::exchange::{cancel::CancelRequest, modify::ModifyRequest, order::OrderRequest};
 pub(crate) use ethers::{
-    abi::{ParamType, Tokenizable, encode},
+    abi::{ParamType, Token, Tokenizable, encode},
     types::{
         H160, U256,
         transaction::{
@@ -15,6 +15,9 @@ use super::{BuilderInfo, cancel::CancelRequestCloid};
 
 pub(crate) const HYPERLIQUID_EIP_PREFIX: &str = "HyperliquidTransaction:";
 
+/// Chain id user signed actions are signed for unless the client is configured otherwise.
+pub const DEFAULT_SIGNATURE_CHAIN_ID: u64 = 421614;
+
 fn eip_712_domain(chain_id: U256) -> EIP712Domain {
     EIP712Domain {
         name: Some("HyperliquidSignTransaction".to_string()),
@@ -29,55 +32,144 @@ fn eip_712_domain(chain_id: U256) -> EIP712Domain {
     }
 }
 
-#[derive(serde::Serialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct UsdSend {
-    pub signature_chain_id: U256,
-    pub hyperliquid_chain: String,
-    pub destination: String,
-    pub amount: String,
-    pub time: u64,
+/// Field of a user signed action, with its EIP-712 type and encoding.
+pub(crate) trait Eip712Field {
+    fn param_type() -> ParamType;
+    fn eip712_token(&self) -> Token;
 }
 
-impl Eip712 for UsdSend {
-    type Error = Eip712Error;
+impl Eip712Field for String {
+    fn param_type() -> ParamType {
+        ParamType::String
+    }
 
-    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
-        Ok(eip_712_domain(self.signature_chain_id))
+    fn eip712_token(&self) -> Token {
+        encode_eip712_type(self.clone().into_token())
     }
+}
 
-    fn type_hash() -> Result<[u8; 32], Self::Error> {
-        Ok(eip712::make_type_hash(
-            format!("{HYPERLIQUID_EIP_PREFIX}UsdSend"),
-            &[
-                ("hyperliquidChain".to_string(), ParamType::String),
-                ("destination".to_string(), ParamType::String),
-                ("amount".to_string(), ParamType::String),
-                ("time".to_string(), ParamType::Uint(64)),
-            ],
-        ))
+/// Encoded as an empty string when missing.
+impl Eip712Field for Option<String> {
+    fn param_type() -> ParamType {
+        ParamType::String
     }
 
-    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
-        let Self {
-            signature_chain_id: _,
-            hyperliquid_chain,
-            destination,
-            amount,
-            time,
-        } = self;
-        let items = vec![
-            ethers::abi::Token::Uint(Self::type_hash()?.into()),
-            encode_eip712_type(hyperliquid_chain.clone().into_token()),
-            encode_eip712_type(destination.clone().into_token()),
-            encode_eip712_type(amount.clone().into_token()),
-            encode_eip712_type(time.into_token()),
-        ];
-        Ok(keccak256(encode(&items)))
+    fn eip712_token(&self) -> Token {
+        self.clone().unwrap_or_default().eip712_token()
+    }
+}
+
+impl Eip712Field for u64 {
+    fn param_type() -> ParamType {
+        ParamType::Uint(64)
+    }
+
+    fn eip712_token(&self) -> Token {
+        encode_eip712_type(self.into_token())
+    }
+}
+
+impl Eip712Field for bool {
+    fn param_type() -> ParamType {
+        ParamType::Bool
+    }
+
+    fn eip712_token(&self) -> Token {
+        encode_eip712_type(self.into_token())
+    }
+}
+
+impl Eip712Field for H160 {
+    fn param_type() -> ParamType {
+        ParamType::Address
+    }
+
+    fn eip712_token(&self) -> Token {
+        encode_eip712_type(self.into_token())
+    }
+}
+
+/// EIP-712 name of a field, the camelCase name it is serialized with.
+fn eip712_field_name(field: &str) -> String {
+    let mut name = String::with_capacity(field.len());
+    let mut upper = false;
+    for c in field.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            name.push(c.to_ascii_uppercase());
+            upper = false;
+        } else {
+            name.push(c);
+        }
+    }
+    name
+}
+
+/// Defines a user signed action and its EIP-712 encoding.
+///
+/// The action gets the `signature_chain_id` and `hyperliquid_chain` fields every user signed
+/// action carries, followed by the listed fields. It is signed as the type
+/// `HyperliquidTransaction:<name>` with `hyperliquidChain` and the listed fields, in order,
+/// so list them in the order the exchange hashes them. Field types need an [`Eip712Field`]
+/// implementation.
+macro_rules! user_signed_action {
+    (
+        $(#[$meta:meta])*
+        pub struct $action:ident($primary_type:literal) {
+            $($(#[$field_meta:meta])* pub $field:ident: $ty:ty,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+        #[serde(rename_all = "camelCase")]
+        pub struct $action {
+            pub signature_chain_id: U256,
+            pub hyperliquid_chain: String,
+            $($(#[$field_meta])* pub $field: $ty,)*
+        }
+
+        impl Eip712 for $action {
+            type Error = Eip712Error;
+
+            fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+                Ok(eip_712_domain(self.signature_chain_id))
+            }
+
+            fn type_hash() -> Result<[u8; 32], Self::Error> {
+                Ok(eip712::make_type_hash(
+                    format!("{HYPERLIQUID_EIP_PREFIX}{}", $primary_type),
+                    &[
+                        ("hyperliquidChain".to_string(), ParamType::String),
+                        $((
+                            eip712_field_name(stringify!($field)),
+                            <$ty as Eip712Field>::param_type(),
+                        ),)*
+                    ],
+                ))
+            }
+
+            fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+                let items = vec![
+                    Token::Uint(Self::type_hash()?.into()),
+                    self.hyperliquid_chain.eip712_token(),
+                    $(self.$field.eip712_token(),)*
+                ];
+                Ok(keccak256(encode(&items)))
+            }
+        }
+    };
+}
+
+user_signed_action! {
+    pub struct UsdSend("UsdSend") {
+        pub destination: String,
+        pub amount: String,
+        pub time: u64,
     }
 }
 
-#[derive(serde::Serialize, Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateLeverage {
     pub asset: u32,
@@ -85,7 +177,7 @@ pub struct UpdateLeverage {
     pub leverage: u32,
 }
 
-#[derive(serde::Serialize, Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateIsolatedMargin {
     pub asset: u32,
@@ -93,7 +185,7 @@ pub struct UpdateIsolatedMargin {
     pub ntli: i64,
 }
 
-#[derive(serde::Serialize, Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub enum Grouping {
     Na,
@@ -101,7 +193,7 @@ pub enum Grouping {
     PositionTpsl,
 }
 
-#[derive(serde::Serialize, Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct BulkOrder {
     pub orders: Vec<OrderRequest>,
@@ -110,186 +202,120 @@ pub struct BulkOrder {
     pub builder: Option<BuilderInfo>,
 }
 
-#[derive(serde::Serialize, Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct BulkCancel {
     pub cancels: Vec<CancelRequest>,
 }
 
-#[derive(serde::Serialize, Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct BulkModify {
     pub modifies: Vec<ModifyRequest>,
 }
 
-#[derive(serde::Serialize, Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct BulkCancelCloid {
     pub cancels: Vec<CancelRequestCloid>,
 }
 
-#[derive(serde::Serialize, Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
-pub struct ApproveAgent {
-    pub signature_chain_id: U256,
-    pub hyperliquid_chain: String,
-    pub agent_address: H160,
-    pub agent_name: Option<String>,
-    pub nonce: u64,
+pub struct TwapOrder {
+    pub twap: TwapRequest,
 }
 
-impl Eip712 for ApproveAgent {
-    type Error = Eip712Error;
-
-    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
-        Ok(eip_712_domain(self.signature_chain_id))
-    }
-
-    fn type_hash() -> Result<[u8; 32], Self::Error> {
-        Ok(eip712::make_type_hash(
-            format!("{HYPERLIQUID_EIP_PREFIX}ApproveAgent"),
-            &[
-                ("hyperliquidChain".to_string(), ParamType::String),
-                ("agentAddress".to_string(), ParamType::Address),
-                ("agentName".to_string(), ParamType::String),
-                ("nonce".to_string(), ParamType::Uint(64)),
-            ],
-        ))
-    }
-
-    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
-        let Self {
-            signature_chain_id: _,
-            hyperliquid_chain,
-            agent_address,
-            agent_name,
-            nonce,
-        } = self;
-        let items = vec![
-            ethers::abi::Token::Uint(Self::type_hash()?.into()),
-            encode_eip712_type(hyperliquid_chain.clone().into_token()),
-            encode_eip712_type(agent_address.into_token()),
-            encode_eip712_type(agent_name.clone().unwrap_or_default().into_token()),
-            encode_eip712_type(nonce.into_token()),
-        ];
-        Ok(keccak256(encode(&items)))
-    }
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct TwapRequest {
+    #[serde(rename = "a", alias = "asset")]
+    pub asset: u32,
+    #[serde(rename = "b", alias = "isBuy")]
+    pub is_buy: bool,
+    #[serde(rename = "s", alias = "sz")]
+    pub sz: String,
+    #[serde(rename = "r", alias = "reduceOnly")]
+    pub reduce_only: bool,
+    #[serde(rename = "m", alias = "minutes")]
+    pub minutes: u32,
+    #[serde(rename = "t", alias = "randomize")]
+    pub randomize: bool,
 }
 
-#[derive(serde::Serialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct Withdraw3 {
-    pub hyperliquid_chain: String,
-    pub signature_chain_id: U256,
-    pub amount: String,
-    pub time: u64,
-    pub destination: String,
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct TwapCancel {
+    #[serde(rename = "a", alias = "asset")]
+    pub asset: u32,
+    #[serde(rename = "t", alias = "twapId")]
+    pub twap_id: u64,
 }
 
-impl Eip712 for Withdraw3 {
-    type Error = Eip712Error;
-
-    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
-        Ok(eip_712_domain(self.signature_chain_id))
-    }
-
-    fn type_hash() -> Result<[u8; 32], Self::Error> {
-        Ok(eip712::make_type_hash(
-            format!("{HYPERLIQUID_EIP_PREFIX}Withdraw"),
-            &[
-                ("hyperliquidChain".to_string(), ParamType::String),
-                ("destination".to_string(), ParamType::String),
-                ("amount".to_string(), ParamType::String),
-                ("time".to_string(), ParamType::Uint(64)),
-            ],
-        ))
+user_signed_action! {
+    pub struct ApproveAgent("ApproveAgent") {
+        pub agent_address: H160,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub agent_name: Option<String>,
+        pub nonce: u64,
     }
+}
 
-    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
-        let Self {
-            signature_chain_id: _,
-            hyperliquid_chain,
-            amount,
-            time,
-            destination,
-        } = self;
-        let items = vec![
-            ethers::abi::Token::Uint(Self::type_hash()?.into()),
-            encode_eip712_type(hyperliquid_chain.clone().into_token()),
-            encode_eip712_type(destination.clone().into_token()),
-            encode_eip712_type(amount.clone().into_token()),
-            encode_eip712_type(time.into_token()),
-        ];
-        Ok(keccak256(encode(&items)))
+user_signed_action! {
+    pub struct Withdraw3("Withdraw") {
+        pub destination: String,
+        pub amount: String,
+        pub time: u64,
     }
 }
 
-#[derive(serde::Serialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct SpotSend {
-    pub hyperliquid_chain: String,
-    pub signature_chain_id: U256,
-    pub destination: String,
-    pub token: String,
-    pub amount: String,
-    pub time: u64,
+user_signed_action! {
+    pub struct SpotSend("SpotSend") {
+        pub destination: String,
+        pub token: String,
+        pub amount: String,
+        pub time: u64,
+    }
 }
 
-impl Eip712 for SpotSend {
-    type Error = Eip712Error;
-
-    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
-        Ok(eip_712_domain(self.signature_chain_id))
+user_signed_action! {
+    pub struct CDeposit("CDeposit") {
+        /// Amount of HYPE in wei (8 decimals) moved from spot to staking balance
+        pub wei: u64,
+        pub nonce: u64,
     }
+}
 
-    fn type_hash() -> Result<[u8; 32], Self::Error> {
-        Ok(eip712::make_type_hash(
-            format!("{HYPERLIQUID_EIP_PREFIX}SpotSend"),
-            &[
-                ("hyperliquidChain".to_string(), ParamType::String),
-                ("destination".to_string(), ParamType::String),
-                ("token".to_string(), ParamType::String),
-                ("amount".to_string(), ParamType::String),
-                ("time".to_string(), ParamType::Uint(64)),
-            ],
-        ))
+user_signed_action! {
+    pub struct CWithdraw("CWithdraw") {
+        /// Amount of HYPE in wei (8 decimals) moved from staking to spot balance
+        pub wei: u64,
+        pub nonce: u64,
     }
+}
 
-    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
-        let Self {
-            signature_chain_id: _,
-            hyperliquid_chain,
-            destination,
-            token,
-            amount,
-            time,
-        } = self;
-        let items = vec![
-            ethers::abi::Token::Uint(Self::type_hash()?.into()),
-            encode_eip712_type(hyperliquid_chain.clone().into_token()),
-            encode_eip712_type(destination.clone().into_token()),
-            encode_eip712_type(token.clone().into_token()),
-            encode_eip712_type(amount.clone().into_token()),
-            encode_eip712_type(time.into_token()),
-        ];
-        Ok(keccak256(encode(&items)))
+user_signed_action! {
+    pub struct TokenDelegate("TokenDelegate") {
+        pub validator: H160,
+        /// Amount of HYPE in wei (8 decimals)
+        pub wei: u64,
+        pub is_undelegate: bool,
+        pub nonce: u64,
     }
 }
 
-#[derive(serde::Serialize, Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct SpotUser {
     pub class_transfer: ClassTransfer,
 }
 
-#[derive(serde::Serialize, Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ClassTransfer {
     pub usdc: u64,
     pub to_perp: bool,
 }
 
-#[derive(serde::Serialize, Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct VaultTransfer {
     pub vault_address: H160,
@@ -297,50 +323,249 @@ pub struct VaultTransfer {
     pub usd: u64,
 }
 
-#[derive(serde::Serialize, Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateVault {
+    pub name: String,
+    pub description: String,
+    /// Initial deposit of the leader, with 6 decimals
+    pub initial_usd: u64,
+    pub nonce: u64,
+}
+
+/// Leaving a field as `None` keeps the current setting.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultModify {
+    pub vault_address: H160,
+    pub allow_deposits: Option<bool>,
+    pub always_close_on_withdraw: Option<bool>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultDistribute {
+    pub vault_address: H160,
+    /// Amount to distribute to the followers, with 6 decimals
+    pub usd: u64,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSubAccount {
+    pub name: String,
+}
+
+/// Payload of the `subAccountTransfer` action. Named apart from the ledger update of the same name.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SubAccountUsdTransfer {
+    pub sub_account_user: H160,
+    pub is_deposit: bool,
+    pub usd: u64,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SubAccountSpotTransfer {
+    pub sub_account_user: H160,
+    pub is_deposit: bool,
+    pub token: String,
+    pub amount: String,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleCancel {
+    /// Time in milliseconds at which all open orders get cancelled. `None` disarms the timer.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<u64>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct SetReferrer {
     pub code: String,
 }
 
-#[derive(serde::Serialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct ApproveBuilderFee {
-    pub max_fee_rate: String,
-    pub builder: H160,
-    pub nonce: u64,
-    pub signature_chain_id: U256,
-    pub hyperliquid_chain: String,
+user_signed_action! {
+    pub struct ApproveBuilderFee("ApproveBuilderFee") {
+        pub max_fee_rate: String,
+        pub builder: H160,
+        pub nonce: u64,
+    }
 }
 
-impl Eip712 for ApproveBuilderFee {
-    type Error = Eip712Error;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn user_signed_type_hash_test() -> Result<(), Eip712Error> {
+        let type_hashes = [
+            (
+                UsdSend::type_hash()?,
+                "UsdSend(string hyperliquidChain,string destination,string amount,uint64 time)",
+            ),
+            (
+                Withdraw3::type_hash()?,
+                "Withdraw(string hyperliquidChain,string destination,string amount,uint64 time)",
+            ),
+            (
+                SpotSend::type_hash()?,
+                "SpotSend(string hyperliquidChain,string destination,string token,string amount,uint64 time)",
+            ),
+            (
+                ApproveAgent::type_hash()?,
+                "ApproveAgent(string hyperliquidChain,address agentAddress,string agentName,uint64 nonce)",
+            ),
+            (
+                ApproveBuilderFee::type_hash()?,
+                "ApproveBuilderFee(string hyperliquidChain,string maxFeeRate,address builder,uint64 nonce)",
+            ),
+            (
+                CDeposit::type_hash()?,
+                "CDeposit(string hyperliquidChain,uint64 wei,uint64 nonce)",
+            ),
+            (
+                CWithdraw::type_hash()?,
+                "CWithdraw(string hyperliquidChain,uint64 wei,uint64 nonce)",
+            ),
+            (
+                TokenDelegate::type_hash()?,
+                "TokenDelegate(string hyperliquidChain,address validator,uint64 wei,bool isUndelegate,uint64 nonce)",
+            ),
+        ];
+
+        for (type_hash, encoded_type) in type_hashes {
+            assert_eq!(
+                type_hash,
+                keccak256(format!("{HYPERLIQUID_EIP_PREFIX}{encoded_type}")),
+                "{encoded_type}"
+            );
+        }
 
-    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
-        Ok(eip_712_domain(self.signature_chain_id))
+        Ok(())
     }
 
-    fn type_hash() -> Result<[u8; 32], Self::Error> {
-        Ok(eip712::make_type_hash(
-            format!("{HYPERLIQUID_EIP_PREFIX}ApproveBuilderFee"),
-            &[
-                ("hyperliquidChain".to_string(), ParamType::String),
-                ("maxFeeRate".to_string(), ParamType::String),
-                ("builder".to_string(), ParamType::Address),
-                ("nonce".to_string(), ParamType::Uint(64)),
-            ],
-        ))
+    #[test]
+    fn schedule_cancel_time_test() -> crate::errors::Result<()> {
+        use crate::{Error, exchange::api::Actions};
+
+        let action = Actions::ScheduleCancel(ScheduleCancel { time: None });
+        assert_eq!(
+            serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?,
+            serde_json::json!({"type": "scheduleCancel"})
+        );
+        let mut expected = vec![0x81, 0xa4];
+        expected.extend(b"type");
+        expected.push(0xae);
+        expected.extend(b"scheduleCancel");
+        assert_eq!(
+            rmp_serde::to_vec_named(&action).map_err(|e| Error::RmpParse(e.to_string()))?,
+            expected
+        );
+
+        let action = Actions::ScheduleCancel(ScheduleCancel {
+            time: Some(123456789),
+        });
+        assert_eq!(
+            serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?,
+            serde_json::json!({"type": "scheduleCancel", "time": 123456789})
+        );
+
+        Ok(())
     }
 
-    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
-        let type_hash = Self::type_hash()?;
-        let items = vec![
-            ethers::abi::Token::Uint(type_hash.into()),
-            encode_eip712_type(self.hyperliquid_chain.clone().into_token()),
-            encode_eip712_type(self.max_fee_rate.clone().into_token()),
-            encode_eip712_type(self.builder.into_token()),
-            encode_eip712_type(self.nonce.into_token()),
+    #[test]
+    fn vault_and_sub_account_wire_format_test() -> crate::errors::Result<()> {
+        use crate::{Error, exchange::api::Actions};
+
+        let vault_address = "0x1719884eb866cb12b2287399b15f7db5e7d775ea"
+            .parse::<H160>()
+            .map_err(|e| Error::GenericParse(e.to_string()))?;
+        let sub_account_user = "0x1d9470d4b963f552e6f671a81619d395877bf409"
+            .parse::<H160>()
+            .map_err(|e| Error::GenericParse(e.to_string()))?;
+        let cases = [
+            (
+                Actions::CreateSubAccount(CreateSubAccount {
+                    name: "example".to_string(),
+                }),
+                0,
+                serde_json::json!({"type": "createSubAccount", "name": "example"}),
+                "0x9a7b5272baf65d28b0589bd50863a42ac35897553a6beb274e625b6faf7d6bb1",
+            ),
+            (
+                Actions::SubAccountTransfer(SubAccountUsdTransfer {
+                    sub_account_user,
+                    is_deposit: true,
+                    usd: 10,
+                }),
+                0,
+                serde_json::json!({
+                    "type": "subAccountTransfer",
+                    "subAccountUser": "0x1d9470d4b963f552e6f671a81619d395877bf409",
+                    "isDeposit": true,
+                    "usd": 10,
+                }),
+                "0xd12f71eba9e3e792812bfdf01a6a92f4d4016bb0541e850b41f770891c0cc447",
+            ),
+            (
+                Actions::CreateVault(CreateVault {
+                    name: "Test vault".to_string(),
+                    description: "Golden vector".to_string(),
+                    initial_usd: 100000000,
+                    nonce: 1583838,
+                }),
+                1583838,
+                serde_json::json!({
+                    "type": "createVault",
+                    "name": "Test vault",
+                    "description": "Golden vector",
+                    "initialUsd": 100000000,
+                    "nonce": 1583838,
+                }),
+                "0xea17cae9713c1d89c1b2299c04fe257bafce1a5e887fad6e2428dc236a28bdb1",
+            ),
+            (
+                Actions::VaultModify(VaultModify {
+                    vault_address,
+                    allow_deposits: Some(true),
+                    always_close_on_withdraw: None,
+                }),
+                1583838,
+                serde_json::json!({
+                    "type": "vaultModify",
+                    "vaultAddress": "0x1719884eb866cb12b2287399b15f7db5e7d775ea",
+                    "allowDeposits": true,
+                    "alwaysCloseOnWithdraw": null,
+                }),
+                "0xce3e8178c1b48a1c2ccb5650be2234a36365ec68ddf4c222b00225242462fb51",
+            ),
+            (
+                Actions::VaultDistribute(VaultDistribute {
+                    vault_address,
+                    usd: 1000000,
+                }),
+                1583838,
+                serde_json::json!({
+                    "type": "vaultDistribute",
+                    "vaultAddress": "0x1719884eb866cb12b2287399b15f7db5e7d775ea",
+                    "usd": 1000000,
+                }),
+                "0x84d23458e8a1b441361355b23574f0e0b8ab0003369c56ff7d0fcd4ef0b1e0fb",
+            ),
         ];
-        Ok(keccak256(encode(&items)))
+
+        for (action, nonce, json, hash) in cases {
+            assert_eq!(
+                serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?,
+                json
+            );
+            assert_eq!(format!("{:?}", action.hash(nonce, None)?), hash, "{json}");
+        }
+
+        Ok(())
     }
 }
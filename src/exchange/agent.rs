@@ -0,0 +1,91 @@
+use std::path::Path;
+
+use ethers::{signers::LocalWallet, utils::hex};
+
+use crate::{Error, errors::Result};
+
+/// Writes the private key of an agent wallet to `path` as hex.
+///
+/// On unix the file is only readable by its owner.
+pub fn save_agent_key(wallet: &LocalWallet, path: impl AsRef<Path>) -> Result<()> {
+    let key = hex::encode(wallet.signer().to_bytes());
+
+    #[cfg(unix)]
+    {
+        use std::{
+            fs::Permissions,
+            io::Write,
+            os::unix::fs::{OpenOptionsExt, PermissionsExt},
+        };
+
+        let mut file = std::fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(path)
+            .map_err(|e| Error::AgentKeyFile(e.to_string()))?;
+        // `mode` only applies to newly created files, an existing one keeps its permissions
+        file.set_permissions(Permissions::from_mode(0o600))
+            .map_err(|e| Error::AgentKeyFile(e.to_string()))?;
+        file.write_all(key.as_bytes())
+            .map_err(|e| Error::AgentKeyFile(e.to_string()))
+    }
+
+    #[cfg(not(unix))]
+    std::fs::write(path, key).map_err(|e| Error::AgentKeyFile(e.to_string()))
+}
+
+/// Reads an agent wallet saved with [`save_agent_key`].
+pub fn load_agent_key(path: impl AsRef<Path>) -> Result<LocalWallet> {
+    let key = std::fs::read_to_string(path).map_err(|e| Error::AgentKeyFile(e.to_string()))?;
+
+    key.trim()
+        .parse()
+        .map_err(|e: ethers::signers::WalletError| Error::PrivateKeyParse(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::signers::Signer;
+
+    #[test]
+    fn agent_key_roundtrip_test() -> Result<()> {
+        let mut rng = ethers::core::rand::thread_rng();
+        let wallet = LocalWallet::new(&mut rng);
+        let path = std::env::temp_dir().join(format!("agent-key-{:x}", wallet.address()));
+
+        save_agent_key(&wallet, &path)?;
+        let loaded = load_agent_key(&path)?;
+        std::fs::remove_file(&path).map_err(|e| Error::AgentKeyFile(e.to_string()))?;
+
+        assert_eq!(loaded.address(), wallet.address());
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn agent_key_existing_file_permissions_test() -> Result<()> {
+        use std::{fs::Permissions, os::unix::fs::PermissionsExt};
+
+        let mut rng = ethers::core::rand::thread_rng();
+        let wallet = LocalWallet::new(&mut rng);
+        let path = std::env::temp_dir().join(format!("agent-key-{:x}", wallet.address()));
+        std::fs::write(&path, "").map_err(|e| Error::AgentKeyFile(e.to_string()))?;
+        std::fs::set_permissions(&path, Permissions::from_mode(0o644))
+            .map_err(|e| Error::AgentKeyFile(e.to_string()))?;
+
+        save_agent_key(&wallet, &path)?;
+        let mode = std::fs::metadata(&path)
+            .map_err(|e| Error::AgentKeyFile(e.to_string()))?
+            .permissions()
+            .mode();
+        let loaded = load_agent_key(&path)?;
+        std::fs::remove_file(&path).map_err(|e| Error::AgentKeyFile(e.to_string()))?;
+
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(loaded.address(), wallet.address());
+        Ok(())
+    }
+}
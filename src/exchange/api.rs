@@ -1,48 +1,62 @@
 use crate::req::NetworkType;
-use crate::signature::sign_typed_data;
 use crate::{
-    BulkCancelCloid, Error, ExchangeResponseStatus,
+    AssetRegistry, BulkCancelCloid, CancelFilter, CancelledOrder, ClosedPosition, Error,
+    MarketCloseParams, MarketOrderParams,
     exchange::{
         ClientCancelRequest, ClientOrderRequest,
         actions::{
             ApproveAgent, ApproveBuilderFee, BulkCancel, BulkModify, BulkOrder, Grouping,
-            SetReferrer, UpdateIsolatedMargin, UpdateLeverage, UsdSend,
+            ScheduleCancel, SetReferrer, UpdateIsolatedMargin, UpdateLeverage, UsdSend,
         },
-        cancel::{CancelRequest, CancelRequestCloid},
-        modify::{ClientModifyRequest, ModifyRequest},
+        modify::ClientModifyRequest,
     },
-    helpers::{next_nonce, uuid_to_hex_string},
     info::client::InfoClient,
-    req::{Endpoint, HttpClient},
-    signature::sign_l1_action,
+    req::HttpClient,
     errors::Result,
 };
-use crate::{ClassTransfer, SpotSend, SpotUser, VaultTransfer, Withdraw3};
+use crate::{
+    CDeposit, CWithdraw, CreateSubAccount, CreateSubAccountResult, CreateVault, CreateVaultResult,
+    SpotDeploy, SpotSend, SpotUser, SubAccountSpotTransfer, SubAccountUsdTransfer, TokenDelegate,
+    TwapCancel, TwapOrder, TwapResult, VaultDistribute, VaultModify, VaultTransfer, Withdraw3,
+};
 
 use ethers::{
     signers::{LocalWallet, Signer},
     types::{H160, H256, Signature},
 };
 
+use super::actions::DEFAULT_SIGNATURE_CHAIN_ID;
+use super::bracket::{BracketOrderStatuses, ClientBracketOrder, ClientPositionTpsl};
 use super::cancel::ClientCancelRequestCloid;
-use super::order::BuilderInfo;
+use super::kill_switch::{cancelled_orders, closed_positions};
+use super::order::{BuilderInfo, Cloid};
+use super::pipeline::{ExchangeCore, post_payload};
+use super::response::{ActionResult, CancelResult, ModifyResult, OrderResult, TransferResult};
+use super::twap::ClientTwapRequest;
 
 #[derive(Debug, Clone)]
 pub struct ExchangeApi {
     pub http_client: HttpClient,
-    pub coin_to_asset: std::sync::Arc<scc::HashMap<String, u32>>,
+    pub asset_registry: std::sync::Arc<AssetRegistry>,
+    /// Chain id user signed actions are signed for
+    pub signature_chain_id: u64,
+    /// Before sending orders with a builder, check that the fee is within the exchange
+    /// limits and that the signer approved at least that fee for the builder.
+    pub check_builder_fee: bool,
 }
 
-#[derive(serde::Serialize)]
+/// Signed request to the exchange endpoint, see [`crate::OfflineSigner`].
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
-pub(in crate::exchange) struct ExchangePayload {
-    pub(in crate::exchange) action: serde_json::Value,
-    pub(in crate::exchange) signature: Signature,
-    pub(in crate::exchange) nonce: u64,
-    pub(in crate::exchange) vault_address: Option<H160>,
+pub struct ExchangePayload {
+    pub action: serde_json::Value,
+    pub signature: Signature,
+    pub nonce: u64,
+    #[serde(default)]
+    pub vault_address: Option<H160>,
 }
 
-#[derive(serde::Serialize, Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 #[serde(tag = "type")]
 #[serde(rename_all = "camelCase")]
 pub enum Actions {
@@ -60,14 +74,24 @@ pub enum Actions {
     SpotSend(SpotSend),
     SetReferrer(SetReferrer),
     ApproveBuilderFee(ApproveBuilderFee),
+    TwapOrder(TwapOrder),
+    TwapCancel(TwapCancel),
+    ScheduleCancel(ScheduleCancel),
+    SpotDeploy(SpotDeploy),
+    CDeposit(CDeposit),
+    CWithdraw(CWithdraw),
+    TokenDelegate(TokenDelegate),
+    CreateVault(CreateVault),
+    VaultModify(VaultModify),
+    VaultDistribute(VaultDistribute),
+    CreateSubAccount(CreateSubAccount),
+    SubAccountTransfer(SubAccountUsdTransfer),
+    SubAccountSpotTransfer(SubAccountSpotTransfer),
 }
 
 impl Actions {
-    pub(in crate::exchange) fn hash(
-        &self,
-        timestamp: u64,
-        vault_address: Option<H160>,
-    ) -> Result<H256> {
+    /// Hash signed for L1 actions, also called the connection id.
+    pub fn hash(&self, timestamp: u64, vault_address: Option<H160>) -> Result<H256> {
         let mut bytes =
             rmp_serde::to_vec_named(self).map_err(|e| Error::RmpParse(e.to_string()))?;
         bytes.extend(timestamp.to_be_bytes());
@@ -80,6 +104,22 @@ impl Actions {
 
         Ok(H256::from(ethers::utils::keccak256(bytes)))
     }
+
+    /// Nonce or time embedded in the action, which the request nonce has to match.
+    pub(crate) fn embedded_nonce(&self) -> Option<u64> {
+        match self {
+            Actions::UsdSend(action) => Some(action.time),
+            Actions::Withdraw3(action) => Some(action.time),
+            Actions::SpotSend(action) => Some(action.time),
+            Actions::ApproveAgent(action) => Some(action.nonce),
+            Actions::ApproveBuilderFee(action) => Some(action.nonce),
+            Actions::CDeposit(action) => Some(action.nonce),
+            Actions::CWithdraw(action) => Some(action.nonce),
+            Actions::TokenDelegate(action) => Some(action.nonce),
+            Actions::CreateVault(action) => Some(action.nonce),
+            _ => None,
+        }
+    }
 }
 
 impl ExchangeApi {
@@ -88,50 +128,33 @@ impl ExchangeApi {
             .http_client(http_client.client.clone())
             .network(network)
             .build();
-        let meta = info.meta().await?;
-        let mut perp_map = {
-            let iter = meta
-                .universe
-                .into_iter()
-                .map(|asset| (asset.name, asset.sz_decimals));
-            scc::HashMap::from_iter(iter)
-        };
-
-        info.spot_meta()
-            .await?
-            .add_to_coin_to_asset_map(&mut perp_map);
+        let asset_registry = AssetRegistry::fetch(&info).await?;
 
         Ok(ExchangeApi {
             http_client: http_client.clone(),
-            coin_to_asset: std::sync::Arc::new(perp_map),
+            asset_registry: std::sync::Arc::new(asset_registry),
+            signature_chain_id: DEFAULT_SIGNATURE_CHAIN_ID,
+            check_builder_fee: false,
         })
     }
 
-    async fn post(
-        &self,
-        action: serde_json::Value,
-        signature: Signature,
-        nonce: u64,
-        vault_address: Option<H160>,
-    ) -> Result<ExchangeResponseStatus> {
-        let exchange_payload = ExchangePayload {
-            action,
-            signature,
-            nonce,
-            vault_address,
-        };
-
-        let res = serde_json::to_string(&exchange_payload)
-            .map_err(|e| Error::JsonParse(e.to_string()))?;
-        tracing::debug!("Sending request {res:?}");
-
-        let output = &self
-            .http_client
-            .post(Endpoint::Exchange, res)
-            .await
-            .map_err(|e| Error::JsonParse(e.to_string()))?;
+    fn core<'a>(&'a self, signer: &'a LocalWallet) -> ExchangeCore<'a> {
+        ExchangeCore {
+            http_client: &self.http_client,
+            asset_registry: &self.asset_registry,
+            wallet: signer,
+            vault_address: None,
+            signature_chain_id: self.signature_chain_id,
+        }
+    }
 
-        serde_json::from_str(output).map_err(|e| Error::JsonParse(e.to_string()))
+    /// Sends a payload signed beforehand, e.g. with an [`crate::OfflineSigner`], and parses
+    /// the response as `T`, e.g. [`crate::OrderResult`].
+    pub async fn submit_signed<T: serde::de::DeserializeOwned>(
+        &self,
+        payload: &ExchangePayload,
+    ) -> Result<T> {
+        post_payload(&self.http_client, payload).await
     }
 
     pub async fn usdc_transfer(
@@ -139,26 +162,8 @@ impl ExchangeApi {
         amount: &str,
         destination: &str,
         signer: &LocalWallet,
-    ) -> Result<ExchangeResponseStatus> {
-        let hyperliquid_chain = if self.http_client.is_mainnet() {
-            "Mainnet".to_string()
-        } else {
-            "Testnet".to_string()
-        };
-
-        let timestamp = next_nonce();
-        let usd_send = UsdSend {
-            signature_chain_id: 421614.into(),
-            hyperliquid_chain,
-            destination: destination.to_string(),
-            amount: amount.to_string(),
-            time: timestamp,
-        };
-        let signature = sign_typed_data(&usd_send, signer)?;
-        let action = serde_json::to_value(Actions::UsdSend(usd_send))
-            .map_err(|e| Error::JsonParse(e.to_string()))?;
-
-        self.post(action, signature, timestamp, None).await
+    ) -> Result<TransferResult> {
+        self.core(signer).usdc_transfer(amount, destination).await
     }
 
     pub async fn class_transfer(
@@ -166,51 +171,127 @@ impl ExchangeApi {
         usdc: f64,
         to_perp: bool,
         signer: &LocalWallet,
-    ) -> Result<ExchangeResponseStatus> {
-        // payload expects usdc without decimals
-        let usdc = (usdc * 1e6).round() as u64;
-
-        let timestamp = next_nonce();
-
-        let action = Actions::SpotUser(SpotUser {
-            class_transfer: ClassTransfer { usdc, to_perp },
-        });
-        let connection_id = action.hash(timestamp, None)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(signer, connection_id, is_mainnet)?;
-
-        self.post(action, signature, timestamp, None).await
+    ) -> Result<TransferResult> {
+        self.core(signer).class_transfer(usdc, to_perp).await
     }
 
+    /// Moves USDC between the vault at `vault_address` and the signer, signed on behalf
+    /// of the vault. Use [`ExchangeApi::vault_transfer_to`] to deposit into or withdraw
+    /// from a vault as a follower.
     pub async fn vault_transfer(
         &self,
         is_deposit: bool,
         usd: u64,
         vault_address: &H160,
         signer: &LocalWallet,
-    ) -> Result<ExchangeResponseStatus> {
-        let timestamp = next_nonce();
-
-        let action = Actions::VaultTransfer(VaultTransfer {
-            vault_address: *vault_address,
-            is_deposit,
-            usd,
-        });
-        let connection_id = action.hash(timestamp, Some(*vault_address))?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(signer, connection_id, is_mainnet)?;
-
-        self.post(action, signature, timestamp, Some(*vault_address))
+    ) -> Result<TransferResult> {
+        self.core(signer)
+            .with_vault(Some(*vault_address))
+            .vault_transfer(*vault_address, is_deposit, usd)
             .await
     }
 
+    /// Moves USDC between the signer and any vault. `usd` has 6 decimals.
+    pub async fn vault_transfer_to(
+        &self,
+        vault_address: H160,
+        is_deposit: bool,
+        usd: u64,
+        signer: &LocalWallet,
+    ) -> Result<TransferResult> {
+        self.core(signer)
+            .vault_transfer(vault_address, is_deposit, usd)
+            .await
+    }
+
+    /// Creates a vault led by the signer. Returns the address of the new vault.
+    pub async fn create_vault(
+        &self,
+        name: String,
+        description: String,
+        initial_usd: u64,
+        signer: &LocalWallet,
+    ) -> Result<CreateVaultResult> {
+        self.core(signer)
+            .create_vault(name, description, initial_usd)
+            .await
+    }
+
+    /// Updates the configuration of a vault led by the signer.
+    pub async fn vault_modify(
+        &self,
+        vault_address: H160,
+        allow_deposits: Option<bool>,
+        always_close_on_withdraw: Option<bool>,
+        signer: &LocalWallet,
+    ) -> Result<ActionResult> {
+        self.core(signer)
+            .vault_modify(vault_address, allow_deposits, always_close_on_withdraw)
+            .await
+    }
+
+    /// Distributes `usd` (6 decimals) of a vault led by the signer to its followers.
+    pub async fn vault_distribute(
+        &self,
+        vault_address: H160,
+        usd: u64,
+        signer: &LocalWallet,
+    ) -> Result<ActionResult> {
+        self.core(signer).vault_distribute(vault_address, usd).await
+    }
+
+    pub async fn market_open(
+        &self,
+        params: MarketOrderParams<'_>,
+        signer: &LocalWallet,
+    ) -> Result<OrderResult> {
+        let order = self.core(signer).market_open_order(params).await?;
+        self.order(order, signer).await
+    }
+
+    pub async fn market_open_with_builder(
+        &self,
+        params: MarketOrderParams<'_>,
+        signer: &LocalWallet,
+        builder: BuilderInfo,
+    ) -> Result<OrderResult> {
+        let order = self.core(signer).market_open_order(params).await?;
+        self.order_with_builder(order, signer, builder).await
+    }
+
+    /// Closes the position of `params.wallet`, or of the signer if not set.
+    pub async fn market_close(
+        &self,
+        params: MarketCloseParams<'_>,
+        signer: &LocalWallet,
+    ) -> Result<OrderResult> {
+        let user = params.wallet.unwrap_or(signer).address();
+        let order = self.core(signer).market_close_order(params, user).await?;
+        self.order(order, signer).await
+    }
+
+    /// Closes every perp position of the signer in a single batch,
+    /// see [`crate::ExchangeClient::flatten_all`].
+    pub async fn flatten_all(&self, signer: &LocalWallet) -> Result<Vec<ClosedPosition>> {
+        let positions = self.core(signer).flatten_orders(signer.address()).await?;
+        if positions.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let orders: Vec<ClientOrderRequest> = positions
+            .iter()
+            .map(|(_, _, order)| order.clone())
+            .collect();
+        let response = self.bulk_order(&orders, signer).await?;
+        let statuses = response.into_statuses(orders.len())?;
+        Ok(closed_positions(positions, statuses))
+    }
+
     pub async fn order(
         &self,
         order: ClientOrderRequest,
         signer: &LocalWallet,
-    ) -> Result<ExchangeResponseStatus> {
+    ) -> Result<OrderResult> {
         self.bulk_order(&[order], signer).await
     }
 
@@ -219,7 +300,7 @@ impl ExchangeApi {
         order: ClientOrderRequest,
         signer: &LocalWallet,
         builder: BuilderInfo,
-    ) -> Result<ExchangeResponseStatus> {
+    ) -> Result<OrderResult> {
         self.bulk_order_with_builder(&[order], signer, builder)
             .await
     }
@@ -228,25 +309,9 @@ impl ExchangeApi {
         &self,
         orders: &[ClientOrderRequest],
         signer: &LocalWallet,
-    ) -> Result<ExchangeResponseStatus> {
-        let timestamp = next_nonce();
-
-        let transformed_orders = orders
-            .iter()
-            .filter_map(|order| order.to_order_request(&self.coin_to_asset).ok())
-            .collect::<Vec<_>>();
-
-        let action = Actions::Order(BulkOrder {
-            orders: transformed_orders,
-            grouping: Grouping::Na,
-            builder: None,
-        });
-        let connection_id = action.hash(timestamp, None)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(signer, connection_id, is_mainnet)?;
-        self.post(action, signature, timestamp, None).await
+    ) -> Result<OrderResult> {
+        self.bulk_order_with_grouping(orders, Grouping::Na, None, signer)
+            .await
     }
 
     pub async fn bulk_order_with_builder(
@@ -254,34 +319,64 @@ impl ExchangeApi {
         orders: &[ClientOrderRequest],
         wallet: &LocalWallet,
         mut builder: BuilderInfo,
-    ) -> Result<ExchangeResponseStatus> {
-        let timestamp = next_nonce();
-
+    ) -> Result<OrderResult> {
         builder.builder = builder.builder.to_lowercase();
 
-        let transformed_orders = orders
-            .iter()
-            .filter_map(|order| order.to_order_request(&self.coin_to_asset).ok())
-            .collect::<Vec<_>>();
+        self.bulk_order_with_grouping(orders, Grouping::Na, Some(builder), wallet)
+            .await
+    }
 
-        let action = Actions::Order(BulkOrder {
-            orders: transformed_orders,
-            grouping: Grouping::Na,
-            builder: Some(builder),
-        });
-        let connection_id = action.hash(timestamp, None)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
+    pub async fn bulk_order_with_grouping(
+        &self,
+        orders: &[ClientOrderRequest],
+        grouping: Grouping,
+        builder: Option<BuilderInfo>,
+        signer: &LocalWallet,
+    ) -> Result<OrderResult> {
+        if let (true, Some(builder)) = (self.check_builder_fee, &builder) {
+            self.core(signer)
+                .check_builder_fee(signer.address(), builder, orders)
+                .await?;
+        }
 
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
-        self.post(action, signature, timestamp, None).await
+        self.core(signer)
+            .bulk_order(orders, grouping, builder)
+            .await
+    }
+
+    /// Places an entry order together with its take profit and/or stop loss.
+    pub async fn bracket_order(
+        &self,
+        order: ClientBracketOrder,
+        signer: &LocalWallet,
+    ) -> Result<BracketOrderStatuses> {
+        let (orders, grouping, legs) = order.into_grouped_orders()?;
+        let response = self
+            .bulk_order_with_grouping(&orders, grouping, None, signer)
+            .await?;
+
+        Ok(BracketOrderStatuses::from_result(response, legs))
+    }
+
+    /// Places a take profit and/or stop loss tied to the current position.
+    pub async fn position_tpsl(
+        &self,
+        tpsl: ClientPositionTpsl,
+        signer: &LocalWallet,
+    ) -> Result<BracketOrderStatuses> {
+        let (orders, grouping, legs) = tpsl.into_grouped_orders()?;
+        let response = self
+            .bulk_order_with_grouping(&orders, grouping, None, signer)
+            .await?;
+
+        Ok(BracketOrderStatuses::from_result(response, legs))
     }
 
     pub async fn cancel(
         &self,
         cancel: ClientCancelRequest,
         wallet: &LocalWallet,
-    ) -> Result<ExchangeResponseStatus> {
+    ) -> Result<CancelResult> {
         self.bulk_cancel(&[cancel], wallet).await
     }
 
@@ -289,38 +384,35 @@ impl ExchangeApi {
         &self,
         cancels: &[ClientCancelRequest],
         wallet: &LocalWallet,
-    ) -> Result<ExchangeResponseStatus> {
-        let timestamp = next_nonce();
-
-        let mut transformed_cancels = Vec::new();
-        for cancel in cancels.iter() {
-            let asset = self
-                .coin_to_asset
-                .read(&cancel.asset, |_, asset| *asset)
-                .ok_or(Error::AssetNotFound)?;
-            transformed_cancels.push(CancelRequest {
-                asset,
-                oid: cancel.oid,
-            });
-        }
-
-        let action = Actions::Cancel(BulkCancel {
-            cancels: transformed_cancels,
-        });
-        let connection_id = action.hash(timestamp, None)?;
+    ) -> Result<CancelResult> {
+        self.core(wallet).bulk_cancel(cancels).await
+    }
 
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
+    /// Cancels the open orders of the signer matching `filter` in a single batch,
+    /// see [`crate::ExchangeClient::cancel_all`].
+    pub async fn cancel_all(
+        &self,
+        filter: CancelFilter,
+        signer: &LocalWallet,
+    ) -> Result<Vec<CancelledOrder>> {
+        let cancels = self
+            .core(signer)
+            .cancel_all_requests(signer.address(), &filter)
+            .await?;
+        if cancels.is_empty() {
+            return Ok(Vec::new());
+        }
 
-        self.post(action, signature, timestamp, None).await
+        let response = self.bulk_cancel(&cancels, signer).await?;
+        let statuses = response.into_statuses(cancels.len())?;
+        Ok(cancelled_orders(cancels, statuses))
     }
 
     pub async fn modify(
         &self,
         modify: ClientModifyRequest,
         wallet: &LocalWallet,
-    ) -> Result<ExchangeResponseStatus> {
+    ) -> Result<ModifyResult> {
         self.bulk_modify(&[modify], wallet).await
     }
 
@@ -328,34 +420,26 @@ impl ExchangeApi {
         &self,
         modifies: &[ClientModifyRequest],
         wallet: &LocalWallet,
-    ) -> Result<ExchangeResponseStatus> {
-        let timestamp = next_nonce();
-
-        let mut transformed_modifies = Vec::new();
-        for modify in modifies.iter() {
-            transformed_modifies.push(ModifyRequest {
-                id: modify.id.clone(),
-                order: modify.order.to_order_request(&self.coin_to_asset)?,
-            });
-        }
-
-        let action = Actions::BatchModify(BulkModify {
-            modifies: transformed_modifies,
-        });
-        let connection_id = action.hash(timestamp, None)?;
-
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
+    ) -> Result<ModifyResult> {
+        self.core(wallet).bulk_modify(modifies).await
+    }
 
-        self.post(action, signature, timestamp, None).await
+    /// Modifies the order tagged with `cloid`, without needing its oid.
+    pub async fn modify_by_cloid(
+        &self,
+        cloid: Cloid,
+        order: ClientOrderRequest,
+        wallet: &LocalWallet,
+    ) -> Result<ModifyResult> {
+        self.modify(ClientModifyRequest::by_cloid(cloid, order), wallet)
+            .await
     }
 
     pub async fn cancel_by_cloid(
         &self,
         cancel: ClientCancelRequestCloid,
         wallet: &LocalWallet,
-    ) -> Result<ExchangeResponseStatus> {
+    ) -> Result<CancelResult> {
         self.bulk_cancel_by_cloid(&[cancel], wallet).await
     }
 
@@ -363,34 +447,25 @@ impl ExchangeApi {
         &self,
         cancels: &[ClientCancelRequestCloid],
         wallet: &LocalWallet,
-    ) -> Result<ExchangeResponseStatus> {
-        let timestamp = next_nonce();
-
-        let mut transformed_cancels: Vec<CancelRequestCloid> = Vec::new();
-        for cancel in cancels.iter() {
-            let asset = self
-                .coin_to_asset
-                .read(&cancel.asset, |_, asset| *asset)
-                .ok_or(Error::AssetNotFound)?;
-            transformed_cancels.push(CancelRequestCloid {
-                asset,
-                cloid: match &cancel.cloid {
-                    crate::Cloid::Uuid(cloid) => uuid_to_hex_string(*cloid),
-                    crate::Cloid::String(cloid) => cloid.clone(),
-                },
-            });
-        }
-
-        let action = Actions::CancelByCloid(BulkCancelCloid {
-            cancels: transformed_cancels,
-        });
+    ) -> Result<CancelResult> {
+        self.core(wallet).bulk_cancel_by_cloid(cancels).await
+    }
 
-        let connection_id = action.hash(timestamp, None)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
+    pub async fn twap_order(
+        &self,
+        twap: ClientTwapRequest,
+        signer: &LocalWallet,
+    ) -> Result<TwapResult> {
+        self.core(signer).twap_order(twap).await
+    }
 
-        self.post(action, signature, timestamp, None).await
+    pub async fn twap_cancel(
+        &self,
+        coin: &str,
+        twap_id: u64,
+        signer: &LocalWallet,
+    ) -> Result<TwapResult> {
+        self.core(signer).twap_cancel(coin, twap_id).await
     }
 
     pub async fn update_leverage(
@@ -399,79 +474,75 @@ impl ExchangeApi {
         coin: &str,
         is_cross: bool,
         wallet: &LocalWallet,
-    ) -> Result<ExchangeResponseStatus> {
-        let timestamp = next_nonce();
-
-        let asset_index = self
-            .coin_to_asset
-            .read(coin, |_, asset| *asset)
-            .ok_or(Error::AssetNotFound)?;
-        let action = Actions::UpdateLeverage(UpdateLeverage {
-            asset: asset_index,
-            is_cross,
-            leverage,
-        });
-        let connection_id = action.hash(timestamp, None)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
-
-        self.post(action, signature, timestamp, None).await
+    ) -> Result<ActionResult> {
+        self.core(wallet)
+            .update_leverage(leverage, coin, is_cross)
+            .await
     }
 
-    pub async fn update_isolated_margin(
+    /// Adds `usdc` of margin to the isolated position in `coin`.
+    pub async fn add_isolated_margin(
         &self,
-        amount: f64,
         coin: &str,
+        usdc: f64,
         wallet: &LocalWallet,
-    ) -> Result<ExchangeResponseStatus> {
-        let amount = (amount * 1_000_000.0).round() as i64;
-        let timestamp = next_nonce();
-
-        let asset_index = self
-            .coin_to_asset
-            .read(coin, |_, asset| *asset)
-            .ok_or(Error::AssetNotFound)?;
-        let action = Actions::UpdateIsolatedMargin(UpdateIsolatedMargin {
-            asset: asset_index,
-            is_buy: true,
-            ntli: amount,
-        });
-        let connection_id = action.hash(timestamp, None)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
+    ) -> Result<ActionResult> {
+        if usdc <= 0.0 {
+            return Err(Error::InvalidMarginAmount(usdc));
+        }
+        self.update_isolated_margin(usdc, coin, wallet).await
+    }
 
-        self.post(action, signature, timestamp, None).await
+    /// Removes `usdc` of margin from the isolated position in `coin`.
+    pub async fn remove_isolated_margin(
+        &self,
+        coin: &str,
+        usdc: f64,
+        wallet: &LocalWallet,
+    ) -> Result<ActionResult> {
+        if usdc <= 0.0 {
+            return Err(Error::InvalidMarginAmount(usdc));
+        }
+        self.update_isolated_margin(-usdc, coin, wallet).await
     }
 
-    pub async fn approve_agent(
+    pub async fn update_isolated_margin(
         &self,
+        amount: f64,
+        coin: &str,
         wallet: &LocalWallet,
-    ) -> Result<(LocalWallet, ExchangeResponseStatus)> {
+    ) -> Result<ActionResult> {
+        self.core(wallet).update_isolated_margin(amount, coin).await
+    }
+
+    pub async fn approve_agent(&self, wallet: &LocalWallet) -> Result<(LocalWallet, ActionResult)> {
         let mut rng = ethers::core::rand::thread_rng();
         let key = LocalWallet::new(&mut rng);
-        let address = key.address();
+        let response = self.approve_agent_with(key.address(), None, wallet).await?;
 
-        let hyperliquid_chain = if self.http_client.is_mainnet() {
-            "Mainnet".to_string()
-        } else {
-            "Testnet".to_string()
-        };
+        Ok((key, response))
+    }
 
-        let nonce = next_nonce();
-        let approve_agent = ApproveAgent {
-            signature_chain_id: 421614.into(),
-            hyperliquid_chain,
-            agent_address: address,
-            agent_name: Default::default(),
-            nonce,
-        };
-        let signature = sign_typed_data(&approve_agent, wallet)?;
-        let action = serde_json::to_value(Actions::ApproveAgent(approve_agent))
-            .map_err(|e| Error::JsonParse(e.to_string()))?;
+    /// Approves `agent_address` to sign actions for the signer's account.
+    pub async fn approve_agent_with(
+        &self,
+        agent_address: H160,
+        agent_name: Option<String>,
+        wallet: &LocalWallet,
+    ) -> Result<ActionResult> {
+        self.core(wallet)
+            .approve_agent(agent_address, agent_name)
+            .await
+    }
 
-        Ok((key, self.post(action, signature, nonce, None).await?))
+    /// Revokes the agent approved under `agent_name` (or the unnamed agent).
+    pub async fn revoke_agent(
+        &self,
+        agent_name: Option<String>,
+        wallet: &LocalWallet,
+    ) -> Result<ActionResult> {
+        self.approve_agent_with(H160::zero(), agent_name, wallet)
+            .await
     }
 
     pub async fn withdraw_from_bridge(
@@ -479,26 +550,35 @@ impl ExchangeApi {
         amount: &str,
         destination: &str,
         wallet: &LocalWallet,
-    ) -> Result<ExchangeResponseStatus> {
-        let hyperliquid_chain = if self.http_client.is_mainnet() {
-            "Mainnet".to_string()
-        } else {
-            "Testnet".to_string()
-        };
+    ) -> Result<TransferResult> {
+        self.core(wallet)
+            .withdraw_from_bridge(amount, destination)
+            .await
+    }
 
-        let timestamp = next_nonce();
-        let withdraw = Withdraw3 {
-            signature_chain_id: 421614.into(),
-            hyperliquid_chain,
-            destination: destination.to_string(),
-            amount: amount.to_string(),
-            time: timestamp,
-        };
-        let signature = sign_typed_data(&withdraw, wallet)?;
-        let action = serde_json::to_value(Actions::Withdraw3(withdraw))
-            .map_err(|e| Error::JsonParse(e.to_string()))?;
+    /// Moves `wei` of HYPE (8 decimals) from the spot balance to the staking balance.
+    pub async fn staking_deposit(&self, wei: u64, signer: &LocalWallet) -> Result<TransferResult> {
+        self.core(signer).staking_deposit(wei).await
+    }
+
+    /// Moves `wei` of HYPE (8 decimals) from the staking balance back to the spot balance.
+    ///
+    /// Withdrawals are subject to the unstaking queue before they reach the spot balance.
+    pub async fn staking_withdraw(&self, wei: u64, signer: &LocalWallet) -> Result<TransferResult> {
+        self.core(signer).staking_withdraw(wei).await
+    }
 
-        self.post(action, signature, timestamp, None).await
+    /// Delegates `wei` of staked HYPE (8 decimals) to a validator, or undelegates it.
+    pub async fn token_delegate(
+        &self,
+        validator: H160,
+        wei: u64,
+        is_undelegate: bool,
+        signer: &LocalWallet,
+    ) -> Result<ActionResult> {
+        self.core(signer)
+            .token_delegate(validator, wei, is_undelegate)
+            .await
     }
 
     pub async fn spot_transfer(
@@ -507,44 +587,71 @@ impl ExchangeApi {
         destination: &str,
         token: &str,
         wallet: &LocalWallet,
-    ) -> Result<ExchangeResponseStatus> {
-        let hyperliquid_chain = if self.http_client.is_mainnet() {
-            "Mainnet".to_string()
-        } else {
-            "Testnet".to_string()
-        };
+    ) -> Result<TransferResult> {
+        self.core(wallet)
+            .spot_transfer(amount, destination, token)
+            .await
+    }
 
-        let timestamp = next_nonce();
-        let spot_send = SpotSend {
-            signature_chain_id: 421614.into(),
-            hyperliquid_chain,
-            destination: destination.to_string(),
-            amount: amount.to_string(),
-            time: timestamp,
-            token: token.to_string(),
-        };
-        let signature = sign_typed_data(&spot_send, wallet)?;
-        let action = serde_json::to_value(Actions::SpotSend(spot_send))
-            .map_err(|e| Error::JsonParse(e.to_string()))?;
+    /// Creates a sub-account of the signer. Returns the address of the new sub-account.
+    pub async fn create_sub_account(
+        &self,
+        name: String,
+        signer: &LocalWallet,
+    ) -> Result<CreateSubAccountResult> {
+        self.core(signer).create_sub_account(name).await
+    }
 
-        self.post(action, signature, timestamp, None).await
+    /// Moves USDC between the signer and one of its sub-accounts. `usd` has 6 decimals.
+    pub async fn sub_account_transfer(
+        &self,
+        sub_account_user: H160,
+        is_deposit: bool,
+        usd: u64,
+        signer: &LocalWallet,
+    ) -> Result<TransferResult> {
+        self.core(signer)
+            .sub_account_transfer(sub_account_user, is_deposit, usd)
+            .await
     }
 
-    pub async fn set_referrer(
+    /// Moves a spot token between the signer and one of its sub-accounts.
+    /// `token` has the `NAME:tokenId` format.
+    pub async fn sub_account_spot_transfer(
         &self,
-        code: String,
-        wallet: &LocalWallet,
-    ) -> Result<ExchangeResponseStatus> {
-        let timestamp = next_nonce();
+        sub_account_user: H160,
+        is_deposit: bool,
+        token: &str,
+        amount: &str,
+        signer: &LocalWallet,
+    ) -> Result<TransferResult> {
+        self.core(signer)
+            .sub_account_spot_transfer(sub_account_user, is_deposit, token, amount)
+            .await
+    }
 
-        let action = Actions::SetReferrer(SetReferrer { code });
+    /// Sends one step of the spot deployment flow, see [`SpotDeploy`]. Signed by the
+    /// deployer signer, never on behalf of a vault.
+    pub async fn spot_deploy(
+        &self,
+        spot_deploy: SpotDeploy,
+        signer: &LocalWallet,
+    ) -> Result<ActionResult> {
+        self.core(signer).spot_deploy(spot_deploy).await
+    }
 
-        let connection_id = action.hash(timestamp, None)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
+    /// Arms the dead man's switch: all open orders get cancelled at `time` (in milliseconds)
+    /// unless the timer is re-armed before. Passing `None` disarms it.
+    pub async fn schedule_cancel(
+        &self,
+        time: Option<u64>,
+        signer: &LocalWallet,
+    ) -> Result<ActionResult> {
+        self.core(signer).schedule_cancel(time).await
+    }
 
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
-        self.post(action, signature, timestamp, None).await
+    pub async fn set_referrer(&self, code: String, wallet: &LocalWallet) -> Result<ActionResult> {
+        self.core(wallet).set_referrer(code).await
     }
 
     pub async fn approve_builder_fee(
@@ -552,26 +659,159 @@ impl ExchangeApi {
         builder: H160,
         max_fee_rate: String,
         signer: &LocalWallet,
-    ) -> Result<ExchangeResponseStatus> {
-        let timestamp = next_nonce();
+    ) -> Result<ActionResult> {
+        self.core(signer)
+            .approve_builder_fee(builder, max_fee_rate)
+            .await
+    }
+}
 
-        let hyperliquid_chain = if self.http_client.is_mainnet() {
-            "Mainnet".to_string()
-        } else {
-            "Testnet".to_string()
-        };
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        BaseUrl, ClientLimit, ClientOrder, ExchangeClient, LimitTif, OfflineSigner,
+        req::test_utils::{MOCK_ENDPOINT, mock_exchange_endpoint},
+    };
+
+    fn get_wallet() -> Result<LocalWallet> {
+        let priv_key = "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e";
+        priv_key
+            .parse::<LocalWallet>()
+            .map_err(|e| Error::Wallet(e.to_string()))
+    }
 
-        let approve_builder_fee = ApproveBuilderFee {
-            signature_chain_id: 421614.into(),
-            hyperliquid_chain,
-            builder,
-            max_fee_rate,
-            nonce: timestamp,
-        };
+    fn asset_registry() -> Result<AssetRegistry> {
+        Ok(AssetRegistry::new(
+            serde_json::from_str(
+                r#"{"universe":[{"name":"ETH","szDecimals":4,"maxLeverage":25}]}"#,
+            )
+            .map_err(|e| Error::JsonParse(e.to_string()))?,
+            serde_json::from_str(r#"{"universe":[],"tokens":[]}"#)
+                .map_err(|e| Error::JsonParse(e.to_string()))?,
+        ))
+    }
 
-        let signature = sign_typed_data(&approve_builder_fee, signer)?;
-        let action = serde_json::to_value(Actions::ApproveBuilderFee(approve_builder_fee))
-            .map_err(|e| Error::JsonParse(e.to_string()))?;
-        self.post(action, signature, timestamp, None).await
+    fn exchange_api() -> Result<ExchangeApi> {
+        Ok(ExchangeApi {
+            http_client: HttpClient {
+                client: reqwest::Client::new(),
+                base_url: BaseUrl::Localhost,
+            },
+            asset_registry: std::sync::Arc::new(asset_registry()?),
+            signature_chain_id: DEFAULT_SIGNATURE_CHAIN_ID,
+            check_builder_fee: false,
+        })
+    }
+
+    fn limit_order() -> ClientOrderRequest {
+        ClientOrderRequest {
+            asset: "ETH".to_string(),
+            is_buy: true,
+            reduce_only: false,
+            limit_px: 2000.0,
+            sz: 0.5,
+            cloid: None,
+            order_type: ClientOrder::Limit(ClientLimit { tif: LimitTif::Gtc }),
+        }
+    }
+
+    /// Signs the action of `payload` again with `nonce`, so payloads sent with different
+    /// nonces can be compared.
+    fn resign(payload: &str, nonce: u64, vault_address: Option<H160>) -> Result<serde_json::Value> {
+        let payload: ExchangePayload =
+            serde_json::from_str(payload).map_err(|e| Error::JsonParse(e.to_string()))?;
+        assert!(payload.is_signed_by(get_wallet()?.address(), NetworkType::Localhost)?);
+
+        let mut action = payload.parse_action()?;
+        if let Actions::UsdSend(usd_send) = &mut action {
+            usd_send.time = nonce;
+        }
+        let mut signer = OfflineSigner::new(get_wallet()?, NetworkType::Localhost);
+        if let Some(vault_address) = vault_address {
+            signer = signer.vault_address(vault_address);
+        }
+
+        serde_json::to_value(signer.sign_with_nonce(action, nonce)?)
+            .map_err(|e| Error::JsonParse(e.to_string()))
+    }
+
+    #[tokio::test]
+    async fn client_and_api_payload_parity_test() -> Result<()> {
+        let _endpoint = MOCK_ENDPOINT.lock().await;
+        let server = mock_exchange_endpoint(&[
+            r#"{"status":"ok","response":{"type":"order","data":{"statuses":[{"resting":{"oid":1}}]}}}"#,
+            r#"{"status":"ok","response":{"type":"order","data":{"statuses":[{"resting":{"oid":1}}]}}}"#,
+            r#"{"status":"ok","response":{"type":"batchModify","data":{"statuses":[{"resting":{"oid":2}}]}}}"#,
+            r#"{"status":"ok","response":{"type":"batchModify","data":{"statuses":[{"resting":{"oid":2}}]}}}"#,
+            r#"{"status":"ok","response":{"type":"default"}}"#,
+            r#"{"status":"ok","response":{"type":"default"}}"#,
+            r#"{"status":"ok","response":{"type":"default"}}"#,
+            r#"{"status":"ok","response":{"type":"default"}}"#,
+        ]);
+
+        let wallet = get_wallet()?;
+        let vault_address = H160::repeat_byte(0x11);
+        let client = ExchangeClient::builder()
+            .wallet(wallet.clone())
+            .network(NetworkType::Localhost)
+            .asset_registry(asset_registry()?)
+            .build()
+            .await?;
+        let vault_client = ExchangeClient::builder()
+            .wallet(wallet.clone())
+            .network(NetworkType::Localhost)
+            .asset_registry(asset_registry()?)
+            .vault_address(vault_address)
+            .build()
+            .await?;
+        let api = exchange_api()?;
+
+        client.order(limit_order()).await?;
+        api.order(limit_order(), &wallet).await?;
+        let modify = || ClientModifyRequest::by_oid(1, limit_order());
+        client.modify(modify()).await?;
+        api.modify(modify(), &wallet).await?;
+        let destination = "0x0D1d9635D0640821d15e323ac8AdADfA9c111414";
+        client.usdc_transfer("1", destination, None).await?;
+        api.usdc_transfer("1", destination, &wallet).await?;
+        vault_client.vault_transfer(true, 5000000).await?;
+        api.vault_transfer(true, 5000000, &vault_address, &wallet)
+            .await?;
+
+        let bodies = server.join().unwrap();
+        assert_eq!(bodies.len(), 8);
+        let vault_addresses = [None, None, None, Some(vault_address)];
+        for (pair, vault_address) in bodies.chunks(2).zip(vault_addresses) {
+            let client_payload: serde_json::Value =
+                serde_json::from_str(&pair[0]).map_err(|e| Error::JsonParse(e.to_string()))?;
+            let nonce = client_payload["nonce"].as_u64().unwrap();
+            assert_eq!(resign(&pair[0], nonce, vault_address)?, client_payload);
+            assert_eq!(resign(&pair[1], nonce, vault_address)?, client_payload);
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn api_builder_fee_check_test() -> Result<()> {
+        let _endpoint = MOCK_ENDPOINT.lock().await;
+        // the builder was approved for 1 bps, the order pays 2 bps
+        let server = mock_exchange_endpoint(&["10"]);
+
+        let mut api = exchange_api()?;
+        api.check_builder_fee = true;
+        let builder = BuilderInfo {
+            builder: "0x8c967E73E7B15087c42A10D344cFf4c96D877f1D".to_string(),
+            fee: 20,
+        };
+        let result = api
+            .order_with_builder(limit_order(), &get_wallet()?, builder)
+            .await;
+
+        assert!(matches!(result, Err(Error::BuilderFee(_))));
+        let bodies = server.join().unwrap();
+        assert_eq!(bodies.len(), 1);
+        assert!(bodies[0].contains(r#""type":"maxBuilderFee""#));
+        Ok(())
     }
 }
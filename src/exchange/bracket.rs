@@ -0,0 +1,253 @@
+use crate::{
+    Error, ExchangeDataStatus, OrderResult,
+    errors::Result,
+    exchange::{
+        actions::Grouping,
+        order::{ClientOrder, ClientOrderRequest, TriggerTpsl},
+    },
+};
+
+/// Entry order with attached take-profit and/or stop-loss legs.
+///
+/// Sent with `normalTpsl` grouping: the legs only become active once the entry fills.
+#[derive(Debug)]
+pub struct ClientBracketOrder {
+    pub entry: ClientOrderRequest,
+    pub take_profit: Option<ClientOrderRequest>,
+    pub stop_loss: Option<ClientOrderRequest>,
+}
+
+/// Take-profit and/or stop-loss attached to the current position.
+///
+/// Sent with `positionTpsl` grouping: the legs track the position size.
+#[derive(Debug)]
+pub struct ClientPositionTpsl {
+    pub take_profit: Option<ClientOrderRequest>,
+    pub stop_loss: Option<ClientOrderRequest>,
+}
+
+/// Per-leg statuses of a bracket or position TP/SL order.
+#[derive(Debug, Clone)]
+pub struct BracketOrderStatuses {
+    pub entry: Option<ExchangeDataStatus>,
+    pub take_profit: Option<ExchangeDataStatus>,
+    pub stop_loss: Option<ExchangeDataStatus>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct BracketLegs {
+    entry: bool,
+    take_profit: bool,
+    stop_loss: bool,
+}
+
+impl ClientBracketOrder {
+    pub(crate) fn into_grouped_orders(
+        self,
+    ) -> Result<(Vec<ClientOrderRequest>, Grouping, BracketLegs)> {
+        if self.take_profit.is_none() && self.stop_loss.is_none() {
+            return Err(Error::InvalidTpslGrouping(
+                "bracket order needs a take profit or a stop loss".to_string(),
+            ));
+        }
+        if matches!(self.entry.order_type, ClientOrder::Trigger(_)) {
+            return Err(Error::InvalidTpslGrouping(
+                "entry order must be a limit order".to_string(),
+            ));
+        }
+
+        let legs = BracketLegs {
+            entry: true,
+            take_profit: self.take_profit.is_some(),
+            stop_loss: self.stop_loss.is_some(),
+        };
+        let mut orders = vec![];
+        for (leg, tpsl) in [
+            (self.take_profit, TriggerTpsl::Tp),
+            (self.stop_loss, TriggerTpsl::Sl),
+        ] {
+            if let Some(leg) = leg {
+                validate_leg(&leg, tpsl, &self.entry.asset, !self.entry.is_buy)?;
+                orders.push(leg);
+            }
+        }
+        orders.insert(0, self.entry);
+
+        Ok((orders, Grouping::NormalTpsl, legs))
+    }
+}
+
+impl ClientPositionTpsl {
+    pub(crate) fn into_grouped_orders(
+        self,
+    ) -> Result<(Vec<ClientOrderRequest>, Grouping, BracketLegs)> {
+        let (asset, is_buy) = match (&self.take_profit, &self.stop_loss) {
+            (Some(leg), _) | (None, Some(leg)) => (leg.asset.clone(), leg.is_buy),
+            (None, None) => {
+                return Err(Error::InvalidTpslGrouping(
+                    "position tpsl needs a take profit or a stop loss".to_string(),
+                ));
+            }
+        };
+
+        let legs = BracketLegs {
+            entry: false,
+            take_profit: self.take_profit.is_some(),
+            stop_loss: self.stop_loss.is_some(),
+        };
+        let mut orders = vec![];
+        for (leg, tpsl) in [
+            (self.take_profit, TriggerTpsl::Tp),
+            (self.stop_loss, TriggerTpsl::Sl),
+        ] {
+            if let Some(leg) = leg {
+                validate_leg(&leg, tpsl, &asset, is_buy)?;
+                orders.push(leg);
+            }
+        }
+
+        Ok((orders, Grouping::PositionTpsl, legs))
+    }
+}
+
+fn validate_leg(
+    leg: &ClientOrderRequest,
+    expected_tpsl: TriggerTpsl,
+    asset: &str,
+    is_buy: bool,
+) -> Result<()> {
+    let ClientOrder::Trigger(trigger) = &leg.order_type else {
+        return Err(Error::InvalidTpslGrouping(format!(
+            "{expected_tpsl:?} leg must be a trigger order"
+        )));
+    };
+    if trigger.tpsl != expected_tpsl {
+        return Err(Error::InvalidTpslGrouping(format!(
+            "{expected_tpsl:?} leg has trigger type {:?}",
+            trigger.tpsl
+        )));
+    }
+    if !leg.reduce_only {
+        return Err(Error::InvalidTpslGrouping(format!(
+            "{expected_tpsl:?} leg must be reduce only"
+        )));
+    }
+    if leg.asset != asset {
+        return Err(Error::InvalidTpslGrouping(format!(
+            "{expected_tpsl:?} leg is for {} instead of {asset}",
+            leg.asset
+        )));
+    }
+    if leg.is_buy != is_buy {
+        return Err(Error::InvalidTpslGrouping(format!(
+            "{expected_tpsl:?} leg is on the wrong side"
+        )));
+    }
+
+    Ok(())
+}
+
+impl BracketOrderStatuses {
+    pub(crate) fn from_result(result: OrderResult, legs: BracketLegs) -> Self {
+        let mut statuses = result.statuses.into_iter();
+
+        let mut next_if = |present: bool| if present { statuses.next() } else { None };
+        Self {
+            entry: next_if(legs.entry),
+            take_profit: next_if(legs.take_profit),
+            stop_loss: next_if(legs.stop_loss),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ClientLimit, ClientTrigger, LimitTif};
+
+    fn trigger(is_buy: bool, tpsl: TriggerTpsl, reduce_only: bool) -> ClientOrderRequest {
+        ClientOrderRequest {
+            asset: "ETH".to_string(),
+            is_buy,
+            reduce_only,
+            limit_px: 2000.0,
+            sz: 1.0,
+            cloid: None,
+            order_type: ClientOrder::Trigger(ClientTrigger {
+                is_market: true,
+                trigger_px: 2000.0,
+                tpsl,
+            }),
+        }
+    }
+
+    fn entry(is_buy: bool) -> ClientOrderRequest {
+        ClientOrderRequest {
+            asset: "ETH".to_string(),
+            is_buy,
+            reduce_only: false,
+            limit_px: 1900.0,
+            sz: 1.0,
+            cloid: None,
+            order_type: ClientOrder::Limit(ClientLimit { tif: LimitTif::Gtc }),
+        }
+    }
+
+    #[test]
+    fn bracket_order_validation_test() {
+        let (orders, grouping, _) = ClientBracketOrder {
+            entry: entry(true),
+            take_profit: Some(trigger(false, TriggerTpsl::Tp, true)),
+            stop_loss: Some(trigger(false, TriggerTpsl::Sl, true)),
+        }
+        .into_grouped_orders()
+        .unwrap();
+        assert_eq!(orders.len(), 3);
+        assert!(matches!(grouping, Grouping::NormalTpsl));
+
+        let wrong_side = ClientBracketOrder {
+            entry: entry(true),
+            take_profit: Some(trigger(true, TriggerTpsl::Tp, true)),
+            stop_loss: None,
+        };
+        assert!(wrong_side.into_grouped_orders().is_err());
+
+        let not_reduce_only = ClientBracketOrder {
+            entry: entry(true),
+            take_profit: None,
+            stop_loss: Some(trigger(false, TriggerTpsl::Sl, false)),
+        };
+        assert!(not_reduce_only.into_grouped_orders().is_err());
+
+        let swapped = ClientBracketOrder {
+            entry: entry(true),
+            take_profit: Some(trigger(false, TriggerTpsl::Sl, true)),
+            stop_loss: None,
+        };
+        assert!(swapped.into_grouped_orders().is_err());
+    }
+
+    #[test]
+    fn position_tpsl_validation_test() {
+        let (orders, grouping, _) = ClientPositionTpsl {
+            take_profit: Some(trigger(true, TriggerTpsl::Tp, true)),
+            stop_loss: Some(trigger(true, TriggerTpsl::Sl, true)),
+        }
+        .into_grouped_orders()
+        .unwrap();
+        assert_eq!(orders.len(), 2);
+        assert!(matches!(grouping, Grouping::PositionTpsl));
+
+        let mixed_sides = ClientPositionTpsl {
+            take_profit: Some(trigger(true, TriggerTpsl::Tp, true)),
+            stop_loss: Some(trigger(false, TriggerTpsl::Sl, true)),
+        };
+        assert!(mixed_sides.into_grouped_orders().is_err());
+
+        let empty = ClientPositionTpsl {
+            take_profit: None,
+            stop_loss: None,
+        };
+        assert!(empty.into_grouped_orders().is_err());
+    }
+}
@@ -1,4 +1,7 @@
-use crate::{BaseUrl, NetworkType, exchange::client::ExchangeClient, req::HttpClient, errors::Result};
+use crate::{
+    AssetRegistry, BaseUrl, DEFAULT_SIGNATURE_CHAIN_ID, InfoClient, MarginCheck, NetworkType,
+    RiskLimits, exchange::client::ExchangeClient, req::HttpClient, errors::Result,
+};
 
 pub struct ExchangeClientBuilder {
     http_client: reqwest::Client,
@@ -6,7 +9,13 @@ pub struct ExchangeClientBuilder {
     wallet: ethers::signers::LocalWallet,
     meta: Option<crate::meta::Meta>,
     vault_address: Option<ethers::types::H160>,
-    coin_to_asset: scc::HashMap<String, u32>,
+    account_address: Option<ethers::types::H160>,
+    check_builder_fee: bool,
+    margin_check: MarginCheck,
+    risk_limits: Option<RiskLimits>,
+    signature_chain_id: u64,
+    asset_registry: Option<AssetRegistry>,
+    perp_dex: Option<String>,
 }
 
 impl Default for ExchangeClientBuilder {
@@ -18,7 +27,13 @@ impl Default for ExchangeClientBuilder {
             wallet: ethers::signers::LocalWallet::new(&mut rng),
             meta: None,
             vault_address: None,
-            coin_to_asset: scc::HashMap::new(),
+            account_address: None,
+            check_builder_fee: false,
+            margin_check: MarginCheck::Off,
+            risk_limits: None,
+            signature_chain_id: DEFAULT_SIGNATURE_CHAIN_ID,
+            asset_registry: None,
+            perp_dex: None,
         }
     }
 }
@@ -49,16 +64,76 @@ impl ExchangeClientBuilder {
         self
     }
 
-    pub fn coin_to_asset<T>(mut self, coin_to_asset: T) -> Self
-    where
-        T: Into<scc::HashMap<String, u32>>,
-    {
-        self.coin_to_asset = coin_to_asset.into();
+    /// Master account to act for when `wallet` is one of its agents.
+    pub fn account_address(mut self, account_address: ethers::types::H160) -> Self {
+        self.account_address = Some(account_address);
+        self
+    }
+
+    /// Before sending orders with a builder, check that the fee is within the exchange
+    /// limits and that the account approved at least that fee for the builder.
+    pub fn check_builder_fee(mut self, check_builder_fee: bool) -> Self {
+        self.check_builder_fee = check_builder_fee;
+        self
+    }
+
+    /// Check order sizes against the margin available to the account before sending them.
+    pub fn margin_check(mut self, margin_check: MarginCheck) -> Self {
+        self.margin_check = margin_check;
+        self
+    }
+
+    /// Enforce local pre-trade limits on every order and modify sent by the client.
+    ///
+    /// Clones of the client share the limits, including the daily loss baseline.
+    pub fn risk_limits(mut self, risk_limits: RiskLimits) -> Self {
+        self.risk_limits = Some(risk_limits);
+        self
+    }
+
+    /// Chain id to sign user signed actions (transfers, withdrawals, approvals) for,
+    /// [`DEFAULT_SIGNATURE_CHAIN_ID`] by default.
+    pub fn signature_chain_id(mut self, signature_chain_id: u64) -> Self {
+        self.signature_chain_id = signature_chain_id;
+        self
+    }
+
+    /// Use a prebuilt asset registry instead of fetching the metadata on build.
+    pub fn asset_registry(mut self, asset_registry: AssetRegistry) -> Self {
+        self.asset_registry = Some(asset_registry);
+        self
+    }
+
+    /// Also trade the perps of the builder-deployed perp dex called `name`, as `name:COIN`.
+    pub fn perp_dex(mut self, name: &str) -> Self {
+        self.perp_dex = Some(name.to_string());
         self
     }
 
     pub async fn build(self) -> Result<ExchangeClient> {
-        ExchangeClient::new(
+        let asset_registry = match &self.perp_dex {
+            Some(dex) => {
+                let info = InfoClient::builder()
+                    .http_client(self.http_client.clone())
+                    .network(self.network.clone())
+                    .build();
+                let mut asset_registry = match self.asset_registry {
+                    Some(asset_registry) => asset_registry,
+                    None => {
+                        let meta = match self.meta.clone() {
+                            Some(meta) => meta,
+                            None => info.meta().await?,
+                        };
+                        AssetRegistry::new(meta, info.spot_meta().await?)
+                    }
+                };
+                asset_registry.fetch_perp_dex(&info, dex).await?;
+                Some(asset_registry)
+            }
+            None => self.asset_registry,
+        };
+
+        let mut client = ExchangeClient::new(
             HttpClient {
                 client: self.http_client,
                 base_url: match self.network {
@@ -70,8 +145,16 @@ impl ExchangeClientBuilder {
             self.wallet,
             self.network,
             self.meta,
+            asset_registry,
             self.vault_address,
+            self.account_address,
         )
-        .await
+        .await?;
+        client.check_builder_fee = self.check_builder_fee;
+        client.margin_check = self.margin_check;
+        client.risk_limits = self.risk_limits.map(std::sync::Arc::new);
+        client.signature_chain_id = self.signature_chain_id;
+
+        Ok(client)
     }
 }
@@ -1,4 +1,4 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug)]
 pub struct ClientCancelRequest {
@@ -6,7 +6,7 @@ pub struct ClientCancelRequest {
     pub oid: u64,
 }
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct CancelRequest {
     #[serde(rename = "a", alias = "asset")]
     pub asset: u32,
@@ -20,7 +20,16 @@ pub struct ClientCancelRequestCloid {
     pub cloid: crate::Cloid,
 }
 
-#[derive(Serialize, Debug, Clone)]
+impl ClientCancelRequestCloid {
+    pub fn new(asset: &str, cloid: crate::Cloid) -> Self {
+        Self {
+            asset: asset.to_string(),
+            cloid,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct CancelRequestCloid {
     pub asset: u32,
     pub cloid: String,
@@ -1,42 +1,57 @@
-use crate::signature::sign_typed_data;
 use crate::{
-    BulkCancelCloid, Error, ExchangeResponseStatus,
+    AssetRegistry, Error,
     exchange::{
-        ClientCancelRequest, ClientOrderRequest,
-        actions::{
-            ApproveAgent, ApproveBuilderFee, BulkCancel, BulkModify, BulkOrder, Grouping,
-            SetReferrer, UpdateIsolatedMargin, UpdateLeverage, UsdSend,
-        },
-        cancel::{CancelRequest, CancelRequestCloid},
-        modify::{ClientModifyRequest, ModifyRequest},
+        ClientCancelRequest, ClientOrderRequest, actions::Grouping, modify::ClientModifyRequest,
     },
-    helpers::{next_nonce, uuid_to_hex_string},
     info::client::InfoClient,
     meta::Meta,
-    req::{Endpoint, HttpClient},
-    signature::sign_l1_action,
+    req::HttpClient,
     errors::Result,
 };
-use crate::{ClassTransfer, SpotSend, SpotUser, VaultTransfer, Withdraw3};
+use crate::{CreateSubAccountResult, CreateVaultResult, SpotDeploy, TwapResult};
 use crate::{ExchangeClientBuilder, req::NetworkType};
 
 use ethers::{
     signers::{LocalWallet, Signer},
-    types::{H160, Signature},
+    types::H160,
 };
+use std::time::Duration;
 
-use super::api::{Actions, ExchangePayload};
+use super::actions::DEFAULT_SIGNATURE_CHAIN_ID;
+use super::api::ExchangePayload;
+use super::bracket::{BracketOrderStatuses, ClientBracketOrder, ClientPositionTpsl};
 use super::cancel::ClientCancelRequestCloid;
-use super::order::{BuilderInfo, LimitTif, MarketCloseParams, MarketOrderParams};
-use super::{ClientLimit, ClientOrder};
+use super::heartbeat::ScheduleCancelHeartbeat;
+use super::kill_switch::{
+    CancelFilter, CancelledOrder, ClosedPosition, cancelled_orders, closed_positions,
+};
+use super::margin_check::{MarginCheck, checked_coins};
+use super::order::{BuilderInfo, Cloid, MarketCloseParams, MarketOrderParams};
+use super::pipeline::{ExchangeCore, post_payload};
+use super::requote::{
+    DesiredQuote, RequotePlan, RequoteResult, RequoteStage, RequotedOrder, RestingQuote,
+    placeable_orders,
+};
+use super::response::{ActionResult, CancelResult, ModifyResult, OrderResult, TransferResult};
+use super::risk::{RiskLimits, RiskSnapshot};
+use super::twap::ClientTwapRequest;
 
 #[derive(Debug, Clone)]
 pub struct ExchangeClient {
     pub http_client: HttpClient,
     pub wallet: std::sync::Arc<LocalWallet>,
     pub vault_address: Option<H160>,
-    pub meta: std::sync::Arc<scc::HashMap<String, u32>>,
-    pub coin_to_asset: std::sync::Arc<scc::HashMap<String, u32>>,
+    /// Master account when `wallet` is one of its agents
+    pub account_address: Option<H160>,
+    /// Verify the builder fee approval before sending orders with a builder
+    pub check_builder_fee: bool,
+    /// Check order sizes against the available margin before sending them
+    pub margin_check: MarginCheck,
+    /// Local pre-trade limits checked before signing orders and modifies
+    pub risk_limits: Option<std::sync::Arc<RiskLimits>>,
+    /// Chain id user signed actions are signed for
+    pub signature_chain_id: u64,
+    pub asset_registry: std::sync::Arc<AssetRegistry>,
 }
 
 impl ExchangeClient {
@@ -51,69 +66,85 @@ impl ExchangeClient {
         wallet: LocalWallet,
         network: NetworkType,
         meta: Option<Meta>,
+        asset_registry: Option<AssetRegistry>,
         vault_address: Option<H160>,
+        account_address: Option<H160>,
     ) -> Result<ExchangeClient> {
-        let info = InfoClient::builder()
-            .http_client(http_client.client.clone())
-            .network(network)
-            .build();
-        let meta = if let Some(meta) = meta {
-            meta
-        } else {
-            info.meta().await?
-        };
-        let mut perp_map = {
-            let iter = meta
-                .universe
-                .iter()
-                .enumerate()
-                .map(|(idx, asset)| (asset.name.clone(), idx as u32));
-            scc::HashMap::from_iter(iter)
-        };
-        info.spot_meta()
-            .await?
-            .add_to_coin_to_asset_map(&mut perp_map);
-
-        let meta = {
-            let iter = meta
-                .universe
-                .into_iter()
-                .map(|asset| (asset.name, asset.sz_decimals));
-            scc::HashMap::from_iter(iter)
+        let asset_registry = match asset_registry {
+            Some(asset_registry) => asset_registry,
+            None => {
+                let info = InfoClient::builder()
+                    .http_client(http_client.client.clone())
+                    .network(network)
+                    .build();
+                let meta = match meta {
+                    Some(meta) => meta,
+                    None => info.meta().await?,
+                };
+                AssetRegistry::new(meta, info.spot_meta().await?)
+            }
         };
+
         Ok(ExchangeClient {
             http_client,
             wallet: std::sync::Arc::new(wallet),
             vault_address,
-            meta: std::sync::Arc::new(meta),
-            coin_to_asset: std::sync::Arc::new(perp_map),
+            account_address,
+            check_builder_fee: false,
+            margin_check: MarginCheck::Off,
+            risk_limits: None,
+            signature_chain_id: DEFAULT_SIGNATURE_CHAIN_ID,
+            asset_registry: std::sync::Arc::new(asset_registry),
         })
     }
 
-    async fn post(
-        &self,
-        action: serde_json::Value,
-        signature: Signature,
-        nonce: u64,
-    ) -> Result<ExchangeResponseStatus> {
-        let exchange_payload = ExchangePayload {
-            action,
-            signature,
-            nonce,
-            vault_address: self.vault_address,
-        };
+    /// Client signing with `agent` on behalf of this client's account.
+    ///
+    /// The agent has to be approved first, see [`ExchangeClient::approve_agent_with`].
+    pub fn with_agent(&self, agent: LocalWallet) -> ExchangeClient {
+        ExchangeClient {
+            wallet: std::sync::Arc::new(agent),
+            account_address: Some(self.account_address()),
+            ..self.clone()
+        }
+    }
 
-        let res = serde_json::to_string(&exchange_payload)
-            .map_err(|e| Error::JsonParse(e.to_string()))?;
-        tracing::debug!("Sending request {res:?}");
+    /// Address of the account trading with this client: the vault or sub-account if set,
+    /// else the master account when signing as an agent, else the wallet's own address.
+    pub fn account_address(&self) -> H160 {
+        self.vault_address
+            .or(self.account_address)
+            .unwrap_or_else(|| self.wallet.address())
+    }
 
-        let output = &self
-            .http_client
-            .post(Endpoint::Exchange, res)
-            .await
-            .map_err(|e| Error::JsonParse(e.to_string()))?;
+    /// Client acting on behalf of `sub_account` through the `vault_address` mechanism.
+    ///
+    /// Orders, cancels and other L1 actions sent with the returned client are signed by this
+    /// client's wallet but executed for the sub-account.
+    pub fn for_sub_account(&self, sub_account: H160) -> ExchangeClient {
+        ExchangeClient {
+            vault_address: Some(sub_account),
+            ..self.clone()
+        }
+    }
+
+    fn core(&self) -> ExchangeCore<'_> {
+        ExchangeCore {
+            http_client: &self.http_client,
+            asset_registry: &self.asset_registry,
+            wallet: &self.wallet,
+            vault_address: self.vault_address,
+            signature_chain_id: self.signature_chain_id,
+        }
+    }
 
-        serde_json::from_str(output).map_err(|e| Error::JsonParse(e.to_string()))
+    /// Sends a payload signed beforehand, e.g. with an [`crate::OfflineSigner`], and parses
+    /// the response as `T`, e.g. [`crate::OrderResult`].
+    pub async fn submit_signed<T: serde::de::DeserializeOwned>(
+        &self,
+        payload: &ExchangePayload,
+    ) -> Result<T> {
+        post_payload(&self.http_client, payload).await
     }
 
     pub async fn usdc_transfer(
@@ -121,27 +152,12 @@ impl ExchangeClient {
         amount: &str,
         destination: &str,
         signer: Option<&LocalWallet>,
-    ) -> Result<ExchangeResponseStatus> {
-        let signer = signer.unwrap_or(&self.wallet);
-        let hyperliquid_chain = if self.http_client.is_mainnet() {
-            "Mainnet".to_string()
-        } else {
-            "Testnet".to_string()
-        };
-
-        let timestamp = next_nonce();
-        let usd_send = UsdSend {
-            signature_chain_id: 421614.into(),
-            hyperliquid_chain,
-            destination: destination.to_string(),
-            amount: amount.to_string(),
-            time: timestamp,
+    ) -> Result<TransferResult> {
+        let core = ExchangeCore {
+            wallet: signer.unwrap_or(&self.wallet),
+            ..self.core()
         };
-        let signature = sign_typed_data(&usd_send, signer)?;
-        let action = serde_json::to_value(Actions::UsdSend(usd_send))
-            .map_err(|e| Error::JsonParse(e.to_string()))?;
-
-        self.post(action, signature, timestamp).await
+        core.usdc_transfer(amount, destination).await
     }
 
     pub async fn class_transfer(
@@ -149,67 +165,67 @@ impl ExchangeClient {
         usdc: f64,
         to_perp: bool,
         signer: Option<&LocalWallet>,
-    ) -> Result<ExchangeResponseStatus> {
-        // payload expects usdc without decimals
-        let usdc = (usdc * 1e6).round() as u64;
-        let signer = signer.unwrap_or(&self.wallet);
-
-        let timestamp = next_nonce();
-
-        let action = Actions::SpotUser(SpotUser {
-            class_transfer: ClassTransfer { usdc, to_perp },
-        });
-        let connection_id = action.hash(timestamp, self.vault_address)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(signer, connection_id, is_mainnet)?;
+    ) -> Result<TransferResult> {
+        let core = ExchangeCore {
+            wallet: signer.unwrap_or(&self.wallet),
+            ..self.core()
+        };
+        core.class_transfer(usdc, to_perp).await
+    }
 
-        self.post(action, signature, timestamp).await
+    /// Moves USDC between the account and the client's `vault_address`.
+    /// Use [`ExchangeClient::vault_transfer_to`] for any other vault.
+    pub async fn vault_transfer(&self, is_deposit: bool, usd: u64) -> Result<TransferResult> {
+        let vault_address = self.vault_address.ok_or(Error::VaultAddressNotFound)?;
+        self.core()
+            .vault_transfer(vault_address, is_deposit, usd)
+            .await
     }
 
-    pub async fn vault_transfer(
+    /// Moves USDC between the account and any vault. `usd` has 6 decimals.
+    pub async fn vault_transfer_to(
         &self,
+        vault_address: H160,
         is_deposit: bool,
         usd: u64,
-    ) -> Result<ExchangeResponseStatus> {
-        let vault_address = match &self.vault_address {
-            Some(vault_address) => vault_address,
-            None => return Err(crate::Error::VaultAddressNotFound),
-        };
-        let timestamp = next_nonce();
-
-        let action = Actions::VaultTransfer(VaultTransfer {
-            vault_address: *vault_address,
-            is_deposit,
-            usd,
-        });
-        let connection_id = action.hash(timestamp, self.vault_address)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(&self.wallet, connection_id, is_mainnet)?;
+    ) -> Result<TransferResult> {
+        self.core()
+            .with_vault(None)
+            .vault_transfer(vault_address, is_deposit, usd)
+            .await
+    }
 
-        self.post(action, signature, timestamp).await
+    /// Creates a vault led by the account. Returns the address of the new vault.
+    pub async fn create_vault(
+        &self,
+        name: String,
+        description: String,
+        initial_usd: u64,
+    ) -> Result<CreateVaultResult> {
+        self.core()
+            .create_vault(name, description, initial_usd)
+            .await
     }
 
-    pub async fn market_open(
+    /// Updates the configuration of a vault led by the account.
+    pub async fn vault_modify(
         &self,
-        params: MarketOrderParams<'_>,
-    ) -> Result<ExchangeResponseStatus> {
-        let slippage = params.slippage.unwrap_or(0.05); // Default 5% slippage
-        let (px, sz_decimals) = self
-            .calculate_slippage_price(params.asset, params.is_buy, slippage, params.px)
-            .await?;
+        vault_address: H160,
+        allow_deposits: Option<bool>,
+        always_close_on_withdraw: Option<bool>,
+    ) -> Result<ActionResult> {
+        self.core()
+            .vault_modify(vault_address, allow_deposits, always_close_on_withdraw)
+            .await
+    }
 
-        let order = ClientOrderRequest {
-            asset: params.asset.to_string(),
-            is_buy: params.is_buy,
-            reduce_only: false,
-            limit_px: px,
-            sz: round_to_decimals(params.sz, sz_decimals),
-            cloid: params.cloid,
-            order_type: ClientOrder::Limit(ClientLimit { tif: LimitTif::Ioc }),
-        };
+    /// Distributes `usd` (6 decimals) of a vault led by the account to its followers.
+    pub async fn vault_distribute(&self, vault_address: H160, usd: u64) -> Result<ActionResult> {
+        self.core().vault_distribute(vault_address, usd).await
+    }
 
+    pub async fn market_open(&self, params: MarketOrderParams<'_>) -> Result<OrderResult> {
+        let order = self.core().market_open_order(params).await?;
         self.order(order).await
     }
 
@@ -217,126 +233,138 @@ impl ExchangeClient {
         &self,
         params: MarketOrderParams<'_>,
         builder: BuilderInfo,
-    ) -> Result<ExchangeResponseStatus> {
-        let slippage = params.slippage.unwrap_or(0.05); // Default 5% slippage
-        let (px, sz_decimals) = self
-            .calculate_slippage_price(params.asset, params.is_buy, slippage, params.px)
-            .await?;
+    ) -> Result<OrderResult> {
+        let order = self.core().market_open_order(params).await?;
+        self.order_with_builder(order, builder).await
+    }
 
-        let order = ClientOrderRequest {
-            asset: params.asset.to_string(),
-            is_buy: params.is_buy,
-            reduce_only: false,
-            limit_px: px,
-            sz: round_to_decimals(params.sz, sz_decimals),
-            cloid: params.cloid,
-            order_type: ClientOrder::Limit(ClientLimit { tif: LimitTif::Ioc }),
-        };
+    pub async fn market_close(&self, params: MarketCloseParams<'_>) -> Result<OrderResult> {
+        let user = params
+            .wallet
+            .map_or_else(|| self.account_address(), |wallet| wallet.address());
+        let order = self.core().market_close_order(params, user).await?;
+        self.order(order).await
+    }
 
-        self.order_with_builder(order, builder).await
+    /// Closes every perp position of the account with reduce only IOC orders at 5% slippage
+    /// from the mid, sent in a single batch. Covers the default perp dex and the perp dexes
+    /// registered with the client.
+    pub async fn flatten_all(&self) -> Result<Vec<ClosedPosition>> {
+        let positions = self.core().flatten_orders(self.account_address()).await?;
+        if positions.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let orders: Vec<ClientOrderRequest> = positions
+            .iter()
+            .map(|(_, _, order)| order.clone())
+            .collect();
+        // Closing orders skip the pre-trade checks, which could otherwise block them
+        let response: OrderResult = self.core().bulk_order(&orders, Grouping::Na, None).await?;
+        let statuses = response.into_statuses(orders.len())?;
+        Ok(closed_positions(positions, statuses))
     }
 
-    pub async fn market_close(
+    /// Applies the configured [`MarginCheck`], returning the orders to send.
+    async fn check_margin<'a>(
         &self,
-        params: MarketCloseParams<'_>,
-    ) -> Result<ExchangeResponseStatus> {
-        let slippage = params.slippage.unwrap_or(0.05); // Default 5% slippage
-        let signer = params.wallet.unwrap_or(&self.wallet);
+        orders: &'a [ClientOrderRequest],
+    ) -> Result<std::borrow::Cow<'a, [ClientOrderRequest]>> {
+        if self.margin_check == MarginCheck::Off {
+            return Ok(std::borrow::Cow::Borrowed(orders));
+        }
 
         let info_client = InfoClient::builder()
+            .http_client(self.http_client.client.clone())
             .network(self.http_client.network_type())
             .build();
-        let user_state = info_client.user_state(signer.address()).await?;
-
-        let position = user_state
-            .asset_positions
-            .iter()
-            .find(|p| p.position.coin == params.asset)
-            .ok_or(Error::AssetNotFound)?;
-
-        let szi = position
-            .position
-            .szi
-            .parse::<f64>()
-            .map_err(|_| Error::FloatStringParse)?;
-
-        let (px, sz_decimals) = self
-            .calculate_slippage_price(params.asset, szi < 0.0, slippage, params.px)
-            .await?;
+        let mut asset_data = std::collections::HashMap::new();
+        for coin in checked_coins(orders, &self.asset_registry) {
+            let data = info_client
+                .active_asset_data(self.account_address(), &coin)
+                .await?;
+            asset_data.insert(coin, data);
+        }
 
-        let sz = round_to_decimals(params.sz.unwrap_or_else(|| szi.abs()), sz_decimals);
+        let mut orders = orders.to_vec();
+        self.margin_check
+            .apply(&mut orders, &asset_data, &self.asset_registry)?;
+        Ok(std::borrow::Cow::Owned(orders))
+    }
 
-        let order = ClientOrderRequest {
-            asset: params.asset.to_string(),
-            is_buy: szi < 0.0,
-            reduce_only: true,
-            limit_px: px,
-            sz,
-            cloid: params.cloid,
-            order_type: ClientOrder::Limit(ClientLimit { tif: LimitTif::Ioc }),
+    /// Checks `orders` against the configured [`RiskLimits`], fetching only the account
+    /// state the limits need.
+    async fn check_risk(&self, orders: &[ClientOrderRequest], new_orders: bool) -> Result<()> {
+        let Some(risk_limits) = &self.risk_limits else {
+            return Ok(());
         };
+        // Reduce only orders are exempt from every limit
+        if orders.iter().all(|order| order.reduce_only) {
+            return Ok(());
+        }
 
-        self.order(order).await
-    }
-
-    async fn calculate_slippage_price(
-        &self,
-        asset: &str,
-        is_buy: bool,
-        slippage: f64,
-        px: Option<f64>,
-    ) -> Result<(f64, u32)> {
         let info_client = InfoClient::builder()
+            .http_client(self.http_client.client.clone())
             .network(self.http_client.network_type())
             .build();
-        let meta = info_client.meta().await?;
-
-        let asset_meta = meta
-            .universe
+        let user = self.account_address();
+        let mut dexes: Vec<&str> = orders
             .iter()
-            .find(|a| a.name == asset)
-            .ok_or(Error::AssetNotFound)?;
-
-        let sz_decimals = asset_meta.sz_decimals;
-        let max_decimals: u32 = if self
-            .coin_to_asset
-            .read(asset, |_, asset| *asset)
-            .unwrap_or_default()
-            < 10000
-        {
-            6
-        } else {
-            8
-        };
-        let price_decimals = max_decimals.saturating_sub(sz_decimals);
-
-        let px = if let Some(px) = px {
-            px
-        } else {
-            let all_mids = info_client.all_mids().await?;
-            all_mids
-                .get(asset)
-                .ok_or(Error::AssetNotFound)?
+            .filter_map(|order| self.asset_registry.asset(&order.asset).ok())
+            .filter_map(|asset| self.asset_registry.perp_dex_name(asset))
+            .collect();
+        dexes.sort();
+        dexes.dedup();
+
+        let mut snapshot = RiskSnapshot::default();
+        if risk_limits.needs_positions() || risk_limits.needs_account_value() {
+            let mut user_states = vec![info_client.user_state(user).await?];
+            snapshot.account_value = user_states[0]
+                .margin_summary
+                .account_value
                 .parse::<f64>()
-                .map_err(|_| Error::FloatStringParse)?
-        };
+                .map_err(|_| Error::FloatStringParse)?;
+            for dex in &dexes {
+                user_states.push(info_client.perp_dex_user_state(user, dex).await?);
+            }
+            for position in user_states.iter().flat_map(|state| &state.asset_positions) {
+                let szi = position
+                    .position
+                    .szi
+                    .parse::<f64>()
+                    .map_err(|_| Error::FloatStringParse)?;
+                snapshot
+                    .positions
+                    .insert(position.position.coin.clone(), szi);
+            }
+        }
 
-        tracing::debug!("px before slippage: {px:?}");
-        let slippage_factor = if is_buy {
-            1.0 + slippage
-        } else {
-            1.0 - slippage
-        };
-        let px = px * slippage_factor;
+        if risk_limits.needs_mids() {
+            let mut all_mids = info_client.all_mids().await?;
+            for dex in &dexes {
+                all_mids.extend(info_client.perp_dex_all_mids(dex).await?);
+            }
+            for order in orders {
+                let name = self
+                    .asset_registry
+                    .name(self.asset_registry.asset(&order.asset)?)?;
+                let mid = all_mids
+                    .get(name)
+                    .ok_or(Error::AssetNotFound)?
+                    .parse::<f64>()
+                    .map_err(|_| Error::FloatStringParse)?;
+                snapshot.mids.insert(order.asset.clone(), mid);
+            }
+        }
 
-        // Round to the correct number of decimal places and significant figures
-        let px = round_to_significant_and_decimal(px, 5, price_decimals);
+        if risk_limits.needs_open_orders() {
+            snapshot.open_orders = self.core().open_orders(user).await?.len();
+        }
 
-        tracing::debug!("px after slippage: {px:?}");
-        Ok((px, sz_decimals))
+        risk_limits.check(orders, new_orders, &snapshot)
     }
 
-    pub async fn order(&self, order: ClientOrderRequest) -> Result<ExchangeResponseStatus> {
+    pub async fn order(&self, order: ClientOrderRequest) -> Result<OrderResult> {
         self.bulk_order(&[order]).await
     }
 
@@ -344,162 +372,210 @@ impl ExchangeClient {
         &self,
         order: ClientOrderRequest,
         builder: BuilderInfo,
-    ) -> Result<ExchangeResponseStatus> {
+    ) -> Result<OrderResult> {
         self.bulk_order_with_builder(&[order], builder).await
     }
 
-    pub async fn bulk_order(
+    pub async fn bulk_order(&self, orders: &[ClientOrderRequest]) -> Result<OrderResult> {
+        self.bulk_order_with_grouping(orders, Grouping::Na, None)
+            .await
+    }
+
+    pub async fn bulk_order_with_builder(
         &self,
         orders: &[ClientOrderRequest],
-    ) -> Result<ExchangeResponseStatus> {
-        let timestamp = next_nonce();
-
-        let transformed_orders = orders
-            .iter()
-            .filter_map(|order| order.to_order_request(&self.coin_to_asset).ok())
-            .collect::<Vec<_>>();
-
-        let action = Actions::Order(BulkOrder {
-            orders: transformed_orders,
-            grouping: Grouping::Na,
-            builder: None,
-        });
-        let connection_id = action.hash(timestamp, self.vault_address)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
+        mut builder: BuilderInfo,
+    ) -> Result<OrderResult> {
+        builder.builder = builder.builder.to_lowercase();
 
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(&self.wallet, connection_id, is_mainnet)?;
-        self.post(action, signature, timestamp).await
+        self.bulk_order_with_grouping(orders, Grouping::Na, Some(builder))
+            .await
     }
 
-    pub async fn bulk_order_with_builder(
+    pub async fn bulk_order_with_grouping(
         &self,
         orders: &[ClientOrderRequest],
-        mut builder: BuilderInfo,
-    ) -> Result<ExchangeResponseStatus> {
-        let timestamp = next_nonce();
+        grouping: Grouping,
+        builder: Option<BuilderInfo>,
+    ) -> Result<OrderResult> {
+        let orders = self.check_margin(orders).await?;
+        let orders = orders.as_ref();
+        self.check_risk(orders, true).await?;
+
+        if let (true, Some(builder)) = (self.check_builder_fee, &builder) {
+            self.core()
+                .check_builder_fee(self.account_address(), builder, orders)
+                .await?;
+        }
 
-        builder.builder = builder.builder.to_lowercase();
+        self.core().bulk_order(orders, grouping, builder).await
+    }
 
-        let transformed_orders = orders
-            .iter()
-            .filter_map(|order| order.to_order_request(&self.coin_to_asset).ok())
-            .collect::<Vec<_>>();
+    /// Places an entry order together with its take profit and/or stop loss.
+    pub async fn bracket_order(&self, order: ClientBracketOrder) -> Result<BracketOrderStatuses> {
+        let (orders, grouping, legs) = order.into_grouped_orders()?;
+        let response = self
+            .bulk_order_with_grouping(&orders, grouping, None)
+            .await?;
 
-        let action = Actions::Order(BulkOrder {
-            orders: transformed_orders,
-            grouping: Grouping::Na,
-            builder: Some(builder),
-        });
-        let connection_id = action.hash(timestamp, self.vault_address)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
+        Ok(BracketOrderStatuses::from_result(response, legs))
+    }
+
+    /// Places a take profit and/or stop loss tied to the current position.
+    pub async fn position_tpsl(&self, tpsl: ClientPositionTpsl) -> Result<BracketOrderStatuses> {
+        let (orders, grouping, legs) = tpsl.into_grouped_orders()?;
+        let response = self
+            .bulk_order_with_grouping(&orders, grouping, None)
+            .await?;
 
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(&self.wallet, connection_id, is_mainnet)?;
-        self.post(action, signature, timestamp).await
+        Ok(BracketOrderStatuses::from_result(response, legs))
     }
 
-    pub async fn cancel(&self, cancel: ClientCancelRequest) -> Result<ExchangeResponseStatus> {
+    pub async fn cancel(&self, cancel: ClientCancelRequest) -> Result<CancelResult> {
         self.bulk_cancel(&[cancel]).await
     }
 
-    pub async fn bulk_cancel(
+    pub async fn bulk_cancel(&self, cancels: &[ClientCancelRequest]) -> Result<CancelResult> {
+        self.core().bulk_cancel(cancels).await
+    }
+
+    /// Cancels the open orders of the account matching `filter` in a single batch. Covers
+    /// the default perp dex, spot and the perp dexes registered with the client.
+    pub async fn cancel_all(&self, filter: CancelFilter) -> Result<Vec<CancelledOrder>> {
+        let cancels = self
+            .core()
+            .cancel_all_requests(self.account_address(), &filter)
+            .await?;
+        if cancels.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let response = self.bulk_cancel(&cancels).await?;
+        let statuses = response.into_statuses(cancels.len())?;
+        Ok(cancelled_orders(cancels, statuses))
+    }
+
+    /// Moves the resting orders of `asset` to the `desired` quotes with as few requests as
+    /// possible. Orders already matching a quote are kept, size decreases are modified in
+    /// place to keep queue priority, and everything else is cancelled and placed in one
+    /// batch each.
+    ///
+    /// Stops at the first batch that fails, recording it in [`RequoteResult::error`]. The
+    /// result still reports the modifies and cancels already applied, so the caller can
+    /// keep track of what is resting.
+    pub async fn requote(
         &self,
-        cancels: &[ClientCancelRequest],
-    ) -> Result<ExchangeResponseStatus> {
-        let timestamp = next_nonce();
-
-        let mut transformed_cancels = Vec::new();
-        for cancel in cancels.iter() {
-            let asset = self
-                .coin_to_asset
-                .read(&cancel.asset, |_, asset| *asset)
-                .ok_or(Error::AssetNotFound)?;
-            transformed_cancels.push(CancelRequest {
-                asset,
-                oid: cancel.oid,
-            });
+        asset: &str,
+        resting: &[RestingQuote],
+        desired: &[DesiredQuote],
+    ) -> RequoteResult {
+        let plan = RequotePlan::new(resting, desired);
+        let cancels = plan.cancel_requests(asset);
+        let mut result = RequoteResult {
+            kept: plan.kept.clone(),
+            ..Default::default()
+        };
+
+        if !plan.modifies.is_empty() {
+            let modifies: Vec<ClientModifyRequest> = plan
+                .modifies
+                .iter()
+                .map(|(oid, quote)| ClientModifyRequest::by_oid(*oid, quote.to_order(asset)))
+                .collect();
+            let statuses = self
+                .bulk_modify(&modifies)
+                .await
+                .and_then(|response| response.into_statuses(modifies.len()));
+            let statuses = match statuses {
+                Ok(statuses) => statuses,
+                Err(error) => return result.failed(RequoteStage::Modify, error),
+            };
+            for ((oid, quote), status) in plan.modifies.into_iter().zip(statuses) {
+                result.requoted.push(RequotedOrder {
+                    quote,
+                    replaced_oid: Some(oid),
+                    status,
+                });
+            }
         }
 
-        let action = Actions::Cancel(BulkCancel {
-            cancels: transformed_cancels,
-        });
-        let connection_id = action.hash(timestamp, self.vault_address)?;
+        if !cancels.is_empty() {
+            let statuses = self
+                .bulk_cancel(&cancels)
+                .await
+                .and_then(|response| response.into_statuses(cancels.len()));
+            let statuses = match statuses {
+                Ok(statuses) => statuses,
+                Err(error) => return result.failed(RequoteStage::Cancel, error),
+            };
+            result.cancelled = cancelled_orders(cancels, statuses);
+        }
 
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(&self.wallet, connection_id, is_mainnet)?;
-        self.post(action, signature, timestamp).await
+        let orders = placeable_orders(plan.orders, &result.cancelled);
+        if !orders.is_empty() {
+            let requests: Vec<ClientOrderRequest> = orders
+                .iter()
+                .map(|(_, quote)| quote.to_order(asset))
+                .collect();
+            let statuses = self
+                .bulk_order(&requests)
+                .await
+                .and_then(|response| response.into_statuses(requests.len()));
+            let statuses = match statuses {
+                Ok(statuses) => statuses,
+                Err(error) => return result.failed(RequoteStage::Order, error),
+            };
+            for ((replaced_oid, quote), status) in orders.into_iter().zip(statuses) {
+                result.requoted.push(RequotedOrder {
+                    quote,
+                    replaced_oid,
+                    status,
+                });
+            }
+        }
+
+        result
     }
 
-    pub async fn modify(&self, modify: ClientModifyRequest) -> Result<ExchangeResponseStatus> {
+    pub async fn modify(&self, modify: ClientModifyRequest) -> Result<ModifyResult> {
         self.bulk_modify(&[modify]).await
     }
 
-    pub async fn bulk_modify(
-        &self,
-        modifies: &[ClientModifyRequest],
-    ) -> Result<ExchangeResponseStatus> {
-        let timestamp = next_nonce();
-
-        let mut transformed_modifies = Vec::new();
-        for modify in modifies.iter() {
-            transformed_modifies.push(ModifyRequest {
-                id: modify.id.clone(),
-                order: modify.order.to_order_request(&self.coin_to_asset)?,
-            });
-        }
-
-        let action = Actions::BatchModify(BulkModify {
-            modifies: transformed_modifies,
-        });
-        let connection_id = action.hash(timestamp, self.vault_address)?;
-
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(&self.wallet, connection_id, is_mainnet)?;
+    pub async fn bulk_modify(&self, modifies: &[ClientModifyRequest]) -> Result<ModifyResult> {
+        let orders: Vec<ClientOrderRequest> =
+            modifies.iter().map(|modify| modify.order.clone()).collect();
+        self.check_risk(&orders, false).await?;
 
-        self.post(action, signature, timestamp).await
+        self.core().bulk_modify(modifies).await
     }
 
-    pub async fn cancel_by_cloid(
+    /// Modifies the order tagged with `cloid`, without needing its oid.
+    pub async fn modify_by_cloid(
         &self,
-        cancel: ClientCancelRequestCloid,
-    ) -> Result<ExchangeResponseStatus> {
+        cloid: Cloid,
+        order: ClientOrderRequest,
+    ) -> Result<ModifyResult> {
+        self.modify(ClientModifyRequest::by_cloid(cloid, order))
+            .await
+    }
+
+    pub async fn cancel_by_cloid(&self, cancel: ClientCancelRequestCloid) -> Result<CancelResult> {
         self.bulk_cancel_by_cloid(&[cancel]).await
     }
 
     pub async fn bulk_cancel_by_cloid(
         &self,
         cancels: &[ClientCancelRequestCloid],
-    ) -> Result<ExchangeResponseStatus> {
-        let timestamp = next_nonce();
-
-        let mut transformed_cancels: Vec<CancelRequestCloid> = Vec::new();
-        for cancel in cancels.iter() {
-            let asset = self
-                .coin_to_asset
-                .read(&cancel.asset, |_, asset| *asset)
-                .ok_or(Error::AssetNotFound)?;
-            transformed_cancels.push(CancelRequestCloid {
-                asset,
-                cloid: match &cancel.cloid {
-                    crate::Cloid::Uuid(cloid) => uuid_to_hex_string(*cloid),
-                    crate::Cloid::String(cloid) => cloid.clone(),
-                },
-            });
-        }
-
-        let action = Actions::CancelByCloid(BulkCancelCloid {
-            cancels: transformed_cancels,
-        });
+    ) -> Result<CancelResult> {
+        self.core().bulk_cancel_by_cloid(cancels).await
+    }
 
-        let connection_id = action.hash(timestamp, self.vault_address)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(&self.wallet, connection_id, is_mainnet)?;
+    pub async fn twap_order(&self, twap: ClientTwapRequest) -> Result<TwapResult> {
+        self.core().twap_order(twap).await
+    }
 
-        self.post(action, signature, timestamp).await
+    pub async fn twap_cancel(&self, coin: &str, twap_id: u64) -> Result<TwapResult> {
+        self.core().twap_cancel(coin, twap_id).await
     }
 
     pub async fn update_leverage(
@@ -507,100 +583,90 @@ impl ExchangeClient {
         leverage: u32,
         coin: &str,
         is_cross: bool,
-    ) -> Result<ExchangeResponseStatus> {
-        let timestamp = next_nonce();
-
-        let asset_index = self
-            .coin_to_asset
-            .read(coin, |_, asset| *asset)
-            .ok_or(Error::AssetNotFound)?;
-        let action = Actions::UpdateLeverage(UpdateLeverage {
-            asset: asset_index,
-            is_cross,
-            leverage,
-        });
-        let connection_id = action.hash(timestamp, self.vault_address)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(&self.wallet, connection_id, is_mainnet)?;
+    ) -> Result<ActionResult> {
+        self.core().update_leverage(leverage, coin, is_cross).await
+    }
 
-        self.post(action, signature, timestamp).await
+    /// Adds `usdc` of margin to the isolated position in `coin`.
+    pub async fn add_isolated_margin(&self, coin: &str, usdc: f64) -> Result<ActionResult> {
+        if usdc <= 0.0 {
+            return Err(Error::InvalidMarginAmount(usdc));
+        }
+        self.update_isolated_margin(usdc, coin).await
     }
 
-    pub async fn update_isolated_margin(
-        &self,
-        amount: f64,
-        coin: &str,
-    ) -> Result<ExchangeResponseStatus> {
-        let amount = (amount * 1_000_000.0).round() as i64;
-        let timestamp = next_nonce();
-
-        let asset_index = self
-            .coin_to_asset
-            .read(coin, |_, asset| *asset)
-            .ok_or(Error::AssetNotFound)?;
-        let action = Actions::UpdateIsolatedMargin(UpdateIsolatedMargin {
-            asset: asset_index,
-            is_buy: true,
-            ntli: amount,
-        });
-        let connection_id = action.hash(timestamp, self.vault_address)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(&self.wallet, connection_id, is_mainnet)?;
+    /// Removes `usdc` of margin from the isolated position in `coin`.
+    pub async fn remove_isolated_margin(&self, coin: &str, usdc: f64) -> Result<ActionResult> {
+        if usdc <= 0.0 {
+            return Err(Error::InvalidMarginAmount(usdc));
+        }
+        self.update_isolated_margin(-usdc, coin).await
+    }
 
-        self.post(action, signature, timestamp).await
+    /// Adds (positive `amount`) or removes (negative `amount`) USDC margin of the
+    /// isolated position in `coin`.
+    pub async fn update_isolated_margin(&self, amount: f64, coin: &str) -> Result<ActionResult> {
+        self.core().update_isolated_margin(amount, coin).await
     }
 
-    pub async fn approve_agent(&self) -> Result<(LocalWallet, ExchangeResponseStatus)> {
+    /// Approves a freshly generated agent wallet.
+    ///
+    /// Save its key with [`crate::save_agent_key`] to keep using it across restarts.
+    pub async fn approve_agent(&self) -> Result<(LocalWallet, ActionResult)> {
         let mut rng = ethers::core::rand::thread_rng();
         let wallet = LocalWallet::new(&mut rng);
-        let address = wallet.address();
+        let response = self.approve_agent_with(wallet.address(), None).await?;
 
-        let hyperliquid_chain = if self.http_client.is_mainnet() {
-            "Mainnet".to_string()
-        } else {
-            "Testnet".to_string()
-        };
+        Ok((wallet, response))
+    }
 
-        let nonce = next_nonce();
-        let approve_agent = ApproveAgent {
-            signature_chain_id: 421614.into(),
-            hyperliquid_chain,
-            agent_address: address,
-            agent_name: Default::default(),
-            nonce,
-        };
-        let signature = sign_typed_data(&approve_agent, &self.wallet)?;
-        let action = serde_json::to_value(Actions::ApproveAgent(approve_agent))
-            .map_err(|e| Error::JsonParse(e.to_string()))?;
-        Ok((wallet, self.post(action, signature, nonce).await?))
+    /// Approves `agent_address` to sign actions for this account.
+    ///
+    /// Named agents replace any previous agent with the same name, the unnamed agent
+    /// replaces the previous unnamed one.
+    pub async fn approve_agent_with(
+        &self,
+        agent_address: H160,
+        agent_name: Option<String>,
+    ) -> Result<ActionResult> {
+        self.core().approve_agent(agent_address, agent_name).await
+    }
+
+    /// Revokes the agent approved under `agent_name` (or the unnamed agent).
+    pub async fn revoke_agent(&self, agent_name: Option<String>) -> Result<ActionResult> {
+        self.approve_agent_with(H160::zero(), agent_name).await
     }
 
     pub async fn withdraw_from_bridge(
         &self,
         amount: &str,
         destination: &str,
-    ) -> Result<ExchangeResponseStatus> {
-        let hyperliquid_chain = if self.http_client.is_mainnet() {
-            "Mainnet".to_string()
-        } else {
-            "Testnet".to_string()
-        };
+    ) -> Result<TransferResult> {
+        self.core().withdraw_from_bridge(amount, destination).await
+    }
 
-        let timestamp = next_nonce();
-        let withdraw = Withdraw3 {
-            signature_chain_id: 421614.into(),
-            hyperliquid_chain,
-            destination: destination.to_string(),
-            amount: amount.to_string(),
-            time: timestamp,
-        };
-        let signature = sign_typed_data(&withdraw, &self.wallet)?;
-        let action = serde_json::to_value(Actions::Withdraw3(withdraw))
-            .map_err(|e| Error::JsonParse(e.to_string()))?;
+    /// Moves `wei` of HYPE (8 decimals) from the spot balance to the staking balance.
+    pub async fn staking_deposit(&self, wei: u64) -> Result<TransferResult> {
+        self.core().staking_deposit(wei).await
+    }
 
-        self.post(action, signature, timestamp).await
+    /// Moves `wei` of HYPE (8 decimals) from the staking balance back to the spot balance.
+    ///
+    /// Withdrawals are subject to the unstaking queue before they reach the spot balance.
+    pub async fn staking_withdraw(&self, wei: u64) -> Result<TransferResult> {
+        self.core().staking_withdraw(wei).await
+    }
+
+    /// Delegates `wei` of staked HYPE (8 decimals) to a validator, or undelegates it.
+    pub async fn token_delegate(
+        &self,
+        validator: H160,
+        wei: u64,
+        is_undelegate: bool,
+    ) -> Result<ActionResult> {
+        self.core()
+            .token_delegate(validator, wei, is_undelegate)
+            .await
     }
 
     pub async fn spot_transfer(
@@ -608,91 +674,92 @@ impl ExchangeClient {
         amount: &str,
         destination: &str,
         token: &str,
-    ) -> Result<ExchangeResponseStatus> {
-        let hyperliquid_chain = if self.http_client.is_mainnet() {
-            "Mainnet".to_string()
-        } else {
-            "Testnet".to_string()
-        };
+    ) -> Result<TransferResult> {
+        self.core().spot_transfer(amount, destination, token).await
+    }
 
-        let timestamp = next_nonce();
-        let spot_send = SpotSend {
-            signature_chain_id: 421614.into(),
-            hyperliquid_chain,
-            destination: destination.to_string(),
-            amount: amount.to_string(),
-            time: timestamp,
-            token: token.to_string(),
-        };
-        let signature = sign_typed_data(&spot_send, &self.wallet)?;
-        let action = serde_json::to_value(Actions::SpotSend(spot_send))
-            .map_err(|e| Error::JsonParse(e.to_string()))?;
+    /// Creates a sub-account of the master account. Returns the address of the new sub-account.
+    pub async fn create_sub_account(&self, name: String) -> Result<CreateSubAccountResult> {
+        self.core().create_sub_account(name).await
+    }
 
-        self.post(action, signature, timestamp).await
+    /// Moves USDC between the master account and a sub-account. `usd` has 6 decimals.
+    pub async fn sub_account_transfer(
+        &self,
+        sub_account_user: H160,
+        is_deposit: bool,
+        usd: u64,
+    ) -> Result<TransferResult> {
+        self.core()
+            .sub_account_transfer(sub_account_user, is_deposit, usd)
+            .await
     }
 
-    pub async fn set_referrer(&self, code: String) -> Result<ExchangeResponseStatus> {
-        let timestamp = next_nonce();
+    /// Moves a spot token between the master account and a sub-account.
+    /// `token` has the same `NAME:tokenId` format as in [`ExchangeClient::spot_transfer`].
+    pub async fn sub_account_spot_transfer(
+        &self,
+        sub_account_user: H160,
+        is_deposit: bool,
+        token: &str,
+        amount: &str,
+    ) -> Result<TransferResult> {
+        self.core()
+            .sub_account_spot_transfer(sub_account_user, is_deposit, token, amount)
+            .await
+    }
 
-        let action = Actions::SetReferrer(SetReferrer { code });
+    /// Sends one step of the spot deployment flow, see [`SpotDeploy`]. Signed by the
+    /// deployer wallet, never on behalf of a vault.
+    pub async fn spot_deploy(&self, spot_deploy: SpotDeploy) -> Result<ActionResult> {
+        self.core().spot_deploy(spot_deploy).await
+    }
 
-        let connection_id = action.hash(timestamp, self.vault_address)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
+    /// Arms the dead man's switch: all open orders get cancelled at `time` (in milliseconds)
+    /// unless the timer is re-armed before. Passing `None` disarms it.
+    pub async fn schedule_cancel(&self, time: Option<u64>) -> Result<ActionResult> {
+        self.core().schedule_cancel(time).await
+    }
+
+    /// Starts a background task re-arming the schedule cancel timer every `interval`,
+    /// each time to `cancel_after` from now. See [`ScheduleCancelHeartbeat`].
+    pub fn schedule_cancel_heartbeat(
+        &self,
+        interval: Duration,
+        cancel_after: Duration,
+    ) -> Result<ScheduleCancelHeartbeat> {
+        ScheduleCancelHeartbeat::start(self.clone(), interval, cancel_after)
+    }
 
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(&self.wallet, connection_id, is_mainnet)?;
-        self.post(action, signature, timestamp).await
+    pub async fn set_referrer(&self, code: String) -> Result<ActionResult> {
+        self.core().set_referrer(code).await
     }
 
     pub async fn approve_builder_fee(
         &self,
         builder: H160,
         max_fee_rate: String,
-    ) -> Result<ExchangeResponseStatus> {
-        let timestamp = next_nonce();
-
-        let hyperliquid_chain = if self.http_client.is_mainnet() {
-            "Mainnet".to_string()
-        } else {
-            "Testnet".to_string()
-        };
-
-        let approve_builder_fee = ApproveBuilderFee {
-            signature_chain_id: 421614.into(),
-            hyperliquid_chain,
-            builder,
-            max_fee_rate,
-            nonce: timestamp,
-        };
-
-        let signature = sign_typed_data(&approve_builder_fee, &self.wallet)?;
-        let action = serde_json::to_value(Actions::ApproveBuilderFee(approve_builder_fee))
-            .map_err(|e| Error::JsonParse(e.to_string()))?;
-        self.post(action, signature, timestamp).await
+    ) -> Result<ActionResult> {
+        self.core().approve_builder_fee(builder, max_fee_rate).await
     }
 }
 
-fn round_to_decimals(value: f64, decimals: u32) -> f64 {
-    let factor = 10f64.powi(decimals as i32);
-    (value * factor).round() / factor
-}
-
-fn round_to_significant_and_decimal(value: f64, sig_figs: u32, max_decimals: u32) -> f64 {
-    let abs_value = value.abs();
-    let magnitude = abs_value.log10().floor() as i32;
-    let scale = 10f64.powi(sig_figs as i32 - magnitude - 1);
-    let rounded = (abs_value * scale).round() / scale;
-    round_to_decimals(rounded.copysign(value), max_decimals)
-}
-
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
 
     use super::*;
     use crate::{
-        Order,
-        exchange::order::{Limit, OrderRequest, Trigger},
+        BulkCancel, BulkOrder, ClientLimit, ClientOrder, ExchangeDataStatus, LimitTif, Order,
+        RiskViolation,
+        exchange::{
+            api::Actions,
+            cancel::CancelRequest,
+            order::{Limit, OrderRequest, Trigger},
+        },
+        helpers::uuid_to_hex_string,
+        req::test_utils::{MOCK_ENDPOINT, mock_exchange_endpoint},
+        signature::sign_l1_action,
     };
 
     fn get_wallet() -> Result<LocalWallet> {
@@ -723,13 +790,13 @@ mod tests {
         let signature = sign_l1_action(&wallet, connection_id, true)?;
         assert_eq!(
             signature.to_string(),
-            "0x82f7e6747e4fcd0359efa9490426871693472d07ce404261f1c39084beb7aba02d8e9e3f618336c2287849b69e5021ac593bb94c00ae82815c7580a4256923a01c"
+            "77957e58e70f43b6b68581f2dc42011fc384538a2e5b7bf42d5b936f19fbb67360721a8598727230f67080efee48c812a6a4442013fd3b0eed509171bef9f23f1c"
         );
 
         let signature = sign_l1_action(&wallet, connection_id, false)?;
         assert_eq!(
             signature.to_string(),
-            "0x1760e47c9670cbc26ca6ad961818231fabcffb116e43feed75baa87c0307cc7c446131e4bd121caeba7fe1e8494410dbf149206988985bb66044f905450638821b"
+            "cd0925372ff1ed499e54883e9a6205ecfadec748f80ec463fe2f84f1209648776377961965cb7b12414186b1ea291e95fd512722427efcbcfb3b0b2bcd4d79d01c"
         );
 
         Ok(())
@@ -758,13 +825,13 @@ mod tests {
         let signature = sign_l1_action(&wallet, connection_id, true)?;
         assert_eq!(
             signature.to_string(),
-            "0xc5cc6ca48c2c4223c89f62f1e6eff4c68546dfc7baa12073a8ddff5a38b3e62a6d2967e080698522863ca147685e5c68ff854348dd97cc032771ff5be301a2c21b"
+            "d3e894092eb27098077145714630a77bbe3836120ee29df7d935d8510b03a08f456de5ec1be82aa65fc6ecda9ef928b0445e212517a98858cfaa251c4cd7552b1c"
         );
 
         let signature = sign_l1_action(&wallet, connection_id, false)?;
         assert_eq!(
             signature.to_string(),
-            "0xeb99e4496d3897aa58c653044c543d347458edfc1a68182fd53c64bcf4c3a6e2429f53e4dee68214f32d3277f7454b77be963d3cb98b8462c79b47adf61185861b"
+            "3768349dbb22a7fd770fc9fc50c7b5124a7da342ea579b309f58002ceae49b4357badc7909770919c45d850aabb08474ff2b7b3204ae5b66d9f7375582981f111c"
         );
 
         Ok(())
@@ -777,13 +844,13 @@ mod tests {
         for (tpsl, mainnet_signature, testnet_signature) in [
             (
                 "tp",
-                "0x5061414c399533a5880f429362ee15511864401aefe00f8a1b6da937a0ecc2e058f90b1b72b26bb79d537785e49b37420b3a04d313ff7010ea8453bbf6e2383c1b",
-                "0x09c29abc493d6144f1136f197194d0cdd87cd8c56c971ee38a69447da5d7a11773355e2d66afff017386e3143654dd07be365530ad382a246f14f818d66be5c81c",
+                "b91e5011dff15e4b4a40753730bda44972132e7b75641f3cac58b66159534a170d422ee1ac3c7a7a2e11e298108a2d6b8da8612caceaeeb3e571de3b2dfda9e41b",
+                "6df38b609904d0d4439884756b8f366f22b3a081801dbdd23f279094a2299fac6424cb0cdc48c3706aeaa368f81959e91059205403d3afd23a55983f710aee871b",
             ),
             (
                 "sl",
-                "0x40fdee49426becc5cabebbcd61182cee72a0ae2c40df3de0cbbcf65621cf64b54dd9d720192c16dedf5aea10aca7bd5522ac76164a3a3f7d10b2054ff732a91e1b",
-                "0x7f2cfdcaa1d8a0b47e4da1699f8aa4af8da749db6bd5ea29f18fdb880856b1705d52ec14e7fc1c37a728b3e6099c83a58c23d5d0775b800c175bf137e29dc0e91c",
+                "8456d2ace666fce1bee1084b00e9620fb20e810368841e9d4dd80eb29014611a0843416e51b1529c22dd2fc28f7ff8f6443875635c72011f60b62cbb8ce90e2d1c",
+                "eb5bdb52297c1d19da45458758bd569dcb24c07e5c7bd52cf76600fd92fdd8213e661e21899c985421ec018a9ee7f3790e7b7d723a9932b7b5adcd7def5354601c",
             ),
         ] {
             let wallet = get_wallet()?;
@@ -829,15 +896,277 @@ mod tests {
         let signature = sign_l1_action(&wallet, connection_id, true)?;
         assert_eq!(
             signature.to_string(),
-            "0x9f8b8530274f2f174adf8cd0f02e8bbf5c2987866fbac000e7e3e19214686dde1018d9d181e95a84246a7361ca1dd731a8cbd42dfb04cfc9b071e78aa487a6441c"
+            "02f76cc5b16e0810152fa0e14e7b219f49c361e3325f771544c6f54e157bf9fa17ed0afc11a98596be85d5cd9f86600aad515337318f7ab346e5ccc1b03425d51b"
         );
 
         let signature = sign_l1_action(&wallet, connection_id, false)?;
         assert_eq!(
             signature.to_string(),
-            "0x6b50910d58758f2a50f9629ac8f01c2ce533d9f5db21446c8f9d3a720e0e5f5e7a2bcf0de855c9af0ddb4959575add4478f5eb153eee8d35b640238ed71314381b"
+            "6ffebadfd48067663390962539fbde76cfa36f53be65abe2ab72c9db6d0db44457720db9d7c4860f142a484f070c84eb4b9694c3a617c83f0d698a27e55fd5e01c"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_spot_deploy_mock_endpoint() -> Result<()> {
+        let _endpoint = MOCK_ENDPOINT.lock().await;
+        let server = mock_exchange_endpoint(&[r#"{"status":"ok","response":{"type":"default"}}"#]);
+
+        let registry = AssetRegistry::new(
+            serde_json::from_str(r#"{"universe":[]}"#)
+                .map_err(|e| Error::JsonParse(e.to_string()))?,
+            serde_json::from_str(r#"{"universe":[],"tokens":[]}"#)
+                .map_err(|e| Error::JsonParse(e.to_string()))?,
+        );
+        let exchange_client = ExchangeClient::builder()
+            .wallet(get_wallet()?)
+            .network(NetworkType::Localhost)
+            .asset_registry(registry)
+            .build()
+            .await?;
+
+        // an error status would be returned as `Error::Exchange`
+        exchange_client
+            .spot_deploy(SpotDeploy::Genesis(crate::Genesis {
+                token: 1,
+                max_supply: "100000000".to_string(),
+                no_hyperliquidity: true,
+            }))
+            .await?;
+
+        let payload: serde_json::Value = serde_json::from_str(&server.join().unwrap()[0])
+            .map_err(|e| Error::JsonParse(e.to_string()))?;
+        assert_eq!(
+            payload["action"],
+            serde_json::json!({
+                "type": "spotDeploy",
+                "genesis": {"token": 1, "maxSupply": "100000000", "noHyperliquidity": true}
+            })
+        );
+        assert!(payload["vaultAddress"].is_null());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_flatten_all_skips_risk_limits() -> Result<()> {
+        let _endpoint = MOCK_ENDPOINT.lock().await;
+        let server = mock_exchange_endpoint(&[
+            r#"{"assetPositions":[{"type":"oneWay","position":{"coin":"ETH","entryPx":"1900.0",
+                "leverage":{"type":"cross","value":5},"liquidationPx":null,"marginUsed":"400.0",
+                "positionValue":"2000.0","returnOnEquity":"0.25","szi":"1.0","unrealizedPnl":"100.0",
+                "maxLeverage":25,"cumFunding":{"allTime":"0.0","sinceOpen":"0.0","sinceChange":"0.0"}}}],
+                "crossMarginSummary":{"accountValue":"10000.0","totalMarginUsed":"400.0","totalNtlPos":"2000.0","totalRawUsd":"8000.0"},
+                "marginSummary":{"accountValue":"10000.0","totalMarginUsed":"400.0","totalNtlPos":"2000.0","totalRawUsd":"8000.0"},
+                "withdrawable":"9600.0"}"#,
+            r#"{"ETH":"2000.0"}"#,
+            r#"{"status":"ok","response":{"type":"order","data":{"statuses":[{"filled":{"totalSz":"1.0","avgPx":"1999.5","oid":1}}]}}}"#,
+        ]);
+
+        let registry = AssetRegistry::new(
+            serde_json::from_str(
+                r#"{"universe":[{"name":"ETH","szDecimals":4,"maxLeverage":25}]}"#,
+            )
+            .map_err(|e| Error::JsonParse(e.to_string()))?,
+            serde_json::from_str(r#"{"universe":[],"tokens":[]}"#)
+                .map_err(|e| Error::JsonParse(e.to_string()))?,
         );
+        let mut risk_limits = RiskLimits::default();
+        risk_limits.price_band = Some(0.01);
+        let exchange_client = ExchangeClient::builder()
+            .wallet(get_wallet()?)
+            .network(NetworkType::Localhost)
+            .asset_registry(registry)
+            .risk_limits(risk_limits)
+            .build()
+            .await?;
 
+        // the close is priced 5% below the mid, outside of the price band
+        let closed = exchange_client.flatten_all().await?;
+        assert_eq!(closed.len(), 1);
+        assert_eq!((closed[0].coin.as_str(), closed[0].szi), ("ETH", 1.0));
+        assert!(matches!(closed[0].status, ExchangeDataStatus::Filled(_)));
+
+        let bodies = server.join().unwrap();
+        let payload: serde_json::Value =
+            serde_json::from_str(&bodies[2]).map_err(|e| Error::JsonParse(e.to_string()))?;
+        assert_eq!(payload["action"]["orders"][0]["r"], true);
+        assert_eq!(payload["action"]["orders"][0]["p"], "1900");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_open_order_limit_counts_perp_dex_orders() -> Result<()> {
+        let _endpoint = MOCK_ENDPOINT.lock().await;
+        let server = mock_exchange_endpoint(&[
+            r#"[{"coin":"ETH","limitPx":"1900.0","oid":1,"side":"B","sz":"1.0","timestamp":1}]"#,
+            r#"[{"coin":"test:ABC","limitPx":"10.0","oid":2,"side":"A","sz":"5","timestamp":2}]"#,
+        ]);
+
+        let mut registry = AssetRegistry::new(
+            serde_json::from_str(
+                r#"{"universe":[{"name":"ETH","szDecimals":4,"maxLeverage":25}]}"#,
+            )
+            .map_err(|e| Error::JsonParse(e.to_string()))?,
+            serde_json::from_str(r#"{"universe":[],"tokens":[]}"#)
+                .map_err(|e| Error::JsonParse(e.to_string()))?,
+        );
+        registry.add_perp_dex(
+            1,
+            "test",
+            serde_json::from_str(
+                r#"{"universe":[{"name":"test:ABC","szDecimals":0,"maxLeverage":10}]}"#,
+            )
+            .map_err(|e| Error::JsonParse(e.to_string()))?,
+        );
+        let mut risk_limits = RiskLimits::default();
+        risk_limits.max_open_orders = Some(2);
+        let exchange_client = ExchangeClient::builder()
+            .wallet(get_wallet()?)
+            .network(NetworkType::Localhost)
+            .asset_registry(registry)
+            .risk_limits(risk_limits)
+            .build()
+            .await?;
+
+        let order = ClientOrderRequest {
+            asset: "ETH".to_string(),
+            is_buy: true,
+            reduce_only: false,
+            limit_px: 1900.0,
+            sz: 1.0,
+            cloid: None,
+            order_type: ClientOrder::Limit(ClientLimit { tif: LimitTif::Gtc }),
+        };
+        let result = exchange_client.order(order).await;
+
+        // only the two open order requests reach the server, the order is rejected
+        assert_eq!(server.join().unwrap().len(), 2);
+        assert!(matches!(
+            result,
+            Err(Error::RiskLimit(RiskViolation::OpenOrders {
+                open_orders: 3,
+                max_open_orders: 2,
+            }))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_master_account_actions_ignore_vault_address() -> Result<()> {
+        let _endpoint = MOCK_ENDPOINT.lock().await;
+        let server = mock_exchange_endpoint(&[
+            r#"{"status":"ok","response":{"type":"createSubAccount","data":"0x1d9470d4b963f552e6f671a81619d395877bf409"}}"#,
+            r#"{"status":"ok","response":{"type":"default"}}"#,
+            r#"{"status":"ok","response":{"type":"createVault","data":"0x1719884eb866cb12b2287399b15f7db5e7d775ea"}}"#,
+            r#"{"status":"ok","response":{"type":"default"}}"#,
+            r#"{"status":"ok","response":{"type":"default"}}"#,
+        ]);
+
+        let registry = AssetRegistry::new(
+            serde_json::from_str(
+                r#"{"universe":[{"name":"ETH","szDecimals":4,"maxLeverage":25}]}"#,
+            )
+            .map_err(|e| Error::JsonParse(e.to_string()))?,
+            serde_json::from_str(r#"{"universe":[],"tokens":[]}"#)
+                .map_err(|e| Error::JsonParse(e.to_string()))?,
+        );
+        let wallet = get_wallet()?;
+        let exchange_client = ExchangeClient::builder()
+            .wallet(wallet.clone())
+            .network(NetworkType::Localhost)
+            .asset_registry(registry)
+            .vault_address(H160::repeat_byte(0x11))
+            .build()
+            .await?;
+
+        let sub_account = exchange_client
+            .create_sub_account("example".to_string())
+            .await?
+            .sub_account_user;
+        exchange_client
+            .sub_account_transfer(sub_account, true, 10)
+            .await?;
+        let vault = exchange_client
+            .create_vault("Test vault".to_string(), "Vault".to_string(), 100000000)
+            .await?
+            .vault_address;
+        exchange_client
+            .vault_modify(vault, Some(true), None)
+            .await?;
+        exchange_client.vault_distribute(vault, 1000000).await?;
+
+        let bodies = server.join().unwrap();
+        assert_eq!(bodies.len(), 5);
+        for body in bodies {
+            let payload: ExchangePayload =
+                serde_json::from_str(&body).map_err(|e| Error::JsonParse(e.to_string()))?;
+            assert_eq!(payload.vault_address, None, "{body}");
+
+            // the signature only verifies for the action hashed without a vault address
+            let action: Actions = serde_json::from_value(payload.action)
+                .map_err(|e| Error::JsonParse(e.to_string()))?;
+            let connection_id = action.hash(payload.nonce, None)?;
+            let signature = sign_l1_action(&wallet, connection_id, false)?;
+            assert_eq!(payload.signature, signature, "{body}");
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_requote_reports_applied_modifies_on_failure() -> Result<()> {
+        let _endpoint = MOCK_ENDPOINT.lock().await;
+        let server = mock_exchange_endpoint(&[
+            r#"{"status":"ok","response":{"type":"batchModify","data":{"statuses":[{"resting":{"oid":3}}]}}}"#,
+            r#"{"status":"err","response":"Too many cancels"}"#,
+        ]);
+
+        let registry = AssetRegistry::new(
+            serde_json::from_str(
+                r#"{"universe":[{"name":"ETH","szDecimals":4,"maxLeverage":25}]}"#,
+            )
+            .map_err(|e| Error::JsonParse(e.to_string()))?,
+            serde_json::from_str(r#"{"universe":[],"tokens":[]}"#)
+                .map_err(|e| Error::JsonParse(e.to_string()))?,
+        );
+        let exchange_client = ExchangeClient::builder()
+            .wallet(get_wallet()?)
+            .network(NetworkType::Localhost)
+            .asset_registry(registry)
+            .build()
+            .await?;
+
+        let resting = |oid, is_buy, px| RestingQuote {
+            oid,
+            is_buy,
+            px,
+            sz: 1.0,
+        };
+        let desired = |is_buy, px, sz| DesiredQuote {
+            is_buy,
+            px,
+            sz,
+            tif: LimitTif::Alo,
+            cloid: None,
+        };
+        // the bid is modified in place, the ask cancelled and placed again
+        let result = exchange_client
+            .requote(
+                "ETH",
+                &[resting(1, true, 1990.0), resting(2, false, 2010.0)],
+                &[desired(true, 1990.0, 0.5), desired(false, 2011.0, 1.0)],
+            )
+            .await;
+
+        assert_eq!(server.join().unwrap().len(), 2);
+        assert_eq!(result.requoted.len(), 1);
+        assert_eq!(result.requoted[0].replaced_oid, Some(1));
+        assert!(matches!(
+            result.requoted[0].status,
+            ExchangeDataStatus::Resting(ref order) if order.oid == 3
+        ));
+        assert!(result.cancelled.is_empty());
+        assert_eq!(result.error.map(|e| e.stage), Some(RequoteStage::Cancel));
         Ok(())
     }
 }
@@ -0,0 +1,125 @@
+use std::time::Duration;
+
+use crate::{
+    ActionResult, Error, ExchangeClient, errors::Result, helpers::now_timestamp_ms,
+    shutdown_signal,
+};
+
+/// Background task keeping the exchange-side `scheduleCancel` timer armed.
+///
+/// Every `interval` the timer is pushed to `cancel_after` from now, so if the process dies
+/// all open orders get cancelled at most `cancel_after` later. The timer is disarmed when
+/// [`ScheduleCancelHeartbeat::stop`] is called or on [`shutdown_signal`].
+#[derive(Debug)]
+pub struct ScheduleCancelHeartbeat {
+    shutdown_tx: tokio::sync::oneshot::Sender<()>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl ScheduleCancelHeartbeat {
+    /// The exchange only accepts cancel times more than 5 seconds in the future.
+    const MIN_CANCEL_AFTER: Duration = Duration::from_secs(5);
+
+    pub(crate) fn start(
+        client: ExchangeClient,
+        interval: Duration,
+        cancel_after: Duration,
+    ) -> Result<Self> {
+        if cancel_after <= Self::MIN_CANCEL_AFTER {
+            return Err(Error::InvalidScheduleCancel(format!(
+                "cancel_after must be more than {:?}",
+                Self::MIN_CANCEL_AFTER
+            )));
+        }
+        if interval.is_zero() || interval >= cancel_after {
+            return Err(Error::InvalidScheduleCancel(
+                "interval must be non-zero and shorter than cancel_after".to_string(),
+            ));
+        }
+
+        let (shutdown_tx, mut shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+        let task = tokio::spawn(async move {
+            let mut heartbeat_interval = tokio::time::interval(interval);
+            heartbeat_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+            loop {
+                tokio::select! {
+                    _ = &mut shutdown_rx => break,
+                    _ = shutdown_signal() => break,
+                    _ = heartbeat_interval.tick() => {
+                        let time = now_timestamp_ms() + cancel_after.as_millis() as u64;
+                        log_response(client.schedule_cancel(Some(time)).await, "arm");
+                    }
+                }
+            }
+
+            log_response(client.schedule_cancel(None).await, "disarm");
+        });
+
+        Ok(Self { shutdown_tx, task })
+    }
+
+    /// Stops the heartbeat and disarms the schedule cancel timer.
+    pub async fn stop(self) {
+        if self.shutdown_tx.send(()).is_err() {
+            tracing::debug!("Schedule cancel heartbeat already stopped");
+        }
+
+        if let Err(e) = self.task.await {
+            tracing::error!("Schedule cancel heartbeat task failed: {e}");
+        }
+    }
+}
+
+fn log_response(response: Result<ActionResult>, action: &str) {
+    match response {
+        Ok(_) => tracing::debug!("Schedule cancel {action}ed"),
+        Err(e) => tracing::error!("Failed to {action} schedule cancel: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AssetRegistry, req::NetworkType};
+
+    #[tokio::test]
+    async fn start_rejects_invalid_durations_test() -> Result<()> {
+        let registry = AssetRegistry::new(
+            serde_json::from_str(r#"{"universe":[]}"#)
+                .map_err(|e| Error::JsonParse(e.to_string()))?,
+            serde_json::from_str(r#"{"universe":[],"tokens":[]}"#)
+                .map_err(|e| Error::JsonParse(e.to_string()))?,
+        );
+        let wallet = "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+            .parse()
+            .map_err(|e: ethers::signers::WalletError| Error::Wallet(e.to_string()))?;
+        let client = ExchangeClient::builder()
+            .wallet(wallet)
+            .network(NetworkType::Localhost)
+            .asset_registry(registry)
+            .build()
+            .await?;
+
+        let secs = Duration::from_secs;
+        for (interval, cancel_after) in [
+            // the deadline must be more than 5 seconds ahead
+            (secs(1), secs(5)),
+            (secs(1), secs(4)),
+            // and each heartbeat must come before the previous deadline
+            (secs(10), secs(10)),
+            (secs(20), secs(10)),
+            (Duration::ZERO, secs(10)),
+        ] {
+            assert!(
+                matches!(
+                    ScheduleCancelHeartbeat::start(client.clone(), interval, cancel_after),
+                    Err(Error::InvalidScheduleCancel(_))
+                ),
+                "{interval:?} {cancel_after:?}"
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,114 @@
+use crate::{ClientCancelRequest, ClientOrderRequest, ExchangeDataStatus, OpenOrdersResponse};
+
+/// Open orders cancelled by [`crate::ExchangeClient::cancel_all`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum CancelFilter {
+    /// Every open order
+    #[default]
+    All,
+    /// Open orders of one coin, named as in the open orders, e.g. `ETH` or `@107`
+    Coin(String),
+    /// Open buy orders
+    Buys,
+    /// Open sell orders
+    Sells,
+}
+
+impl CancelFilter {
+    pub(crate) fn matches(&self, order: &OpenOrdersResponse) -> bool {
+        match self {
+            CancelFilter::All => true,
+            CancelFilter::Coin(coin) => order.coin == *coin,
+            CancelFilter::Buys => order.side == "B",
+            CancelFilter::Sells => order.side == "A",
+        }
+    }
+}
+
+/// Status of one order cancelled by [`crate::ExchangeClient::cancel_all`].
+#[derive(Debug, Clone)]
+pub struct CancelledOrder {
+    pub coin: String,
+    pub oid: u64,
+    pub status: ExchangeDataStatus,
+}
+
+/// Status of the order closing one position in [`crate::ExchangeClient::flatten_all`].
+#[derive(Debug, Clone)]
+pub struct ClosedPosition {
+    pub coin: String,
+    /// Signed size of the position before closing it
+    pub szi: f64,
+    pub status: ExchangeDataStatus,
+}
+
+pub(crate) fn cancelled_orders(
+    cancels: Vec<ClientCancelRequest>,
+    statuses: Vec<ExchangeDataStatus>,
+) -> Vec<CancelledOrder> {
+    cancels
+        .into_iter()
+        .zip(statuses)
+        .map(|(cancel, status)| CancelledOrder {
+            coin: cancel.asset,
+            oid: cancel.oid,
+            status,
+        })
+        .collect()
+}
+
+/// Pairs the coin and signed size of each flattened position with its order status.
+pub(crate) fn closed_positions(
+    positions: Vec<(String, f64, ClientOrderRequest)>,
+    statuses: Vec<ExchangeDataStatus>,
+) -> Vec<ClosedPosition> {
+    positions
+        .into_iter()
+        .zip(statuses)
+        .map(|((coin, szi, _), status)| ClosedPosition { coin, szi, status })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CancelResult, ExchangeResponseStatus};
+
+    #[test]
+    fn cancel_filter_test() {
+        let orders: Vec<OpenOrdersResponse> = serde_json::from_str(
+            r#"[
+                {"coin":"ETH","limitPx":"2000.0","oid":1,"side":"B","sz":"1.0","timestamp":0},
+                {"coin":"ETH","limitPx":"2100.0","oid":2,"side":"A","sz":"1.0","timestamp":0},
+                {"coin":"@107","limitPx":"10.0","oid":3,"side":"B","sz":"5.0","timestamp":0}
+            ]"#,
+        )
+        .unwrap();
+        let matching = |filter: CancelFilter| -> Vec<u64> {
+            orders
+                .iter()
+                .filter(|order| filter.matches(order))
+                .map(|order| order.oid)
+                .collect()
+        };
+
+        assert_eq!(matching(CancelFilter::All), vec![1, 2, 3]);
+        assert_eq!(matching(CancelFilter::Coin("ETH".to_string())), vec![1, 2]);
+        assert_eq!(matching(CancelFilter::Buys), vec![1, 3]);
+        assert_eq!(matching(CancelFilter::Sells), vec![2]);
+    }
+
+    #[test]
+    fn batch_statuses_test() {
+        let response: ExchangeResponseStatus<CancelResult> = serde_json::from_str(
+            r#"{"status":"ok","response":{"type":"cancel","data":{"statuses":["success",{"error":"Order was never placed, already canceled, or filled."}]}}}"#,
+        )
+        .unwrap();
+        let response = response.into_result().unwrap();
+
+        let statuses = response.clone().into_statuses(2).unwrap();
+        assert!(matches!(statuses[0], ExchangeDataStatus::Success));
+        assert!(matches!(statuses[1], ExchangeDataStatus::Error(_)));
+        assert!(response.into_statuses(3).is_err());
+    }
+}
@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+
+use crate::{ActiveAssetDataData, AssetRegistry, ClientOrderRequest, Error, errors::Result};
+
+/// Pre-trade check of order sizes against the margin available to the account.
+///
+/// Sizes are compared with the `maxTradeSzs` of the active asset data, which account for
+/// the current leverage, position and available margin. Reduce only orders and spot orders
+/// are never checked.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MarginCheck {
+    /// Orders are sent as they are
+    #[default]
+    Off,
+    /// Orders above the maximum tradable size are rejected locally
+    Reject,
+    /// Orders above the maximum tradable size are reduced to it
+    Clamp,
+}
+
+impl MarginCheck {
+    /// Applies the check to `orders`. `asset_data` holds the active asset data of every
+    /// checked coin, keyed by coin.
+    pub(crate) fn apply(
+        self,
+        orders: &mut [ClientOrderRequest],
+        asset_data: &HashMap<String, ActiveAssetDataData>,
+        asset_registry: &AssetRegistry,
+    ) -> Result<()> {
+        if self == MarginCheck::Off {
+            return Ok(());
+        }
+
+        for order in orders.iter_mut().filter(|order| !order.reduce_only) {
+            let asset = asset_registry.asset(&order.asset)?;
+            if asset.is_spot() {
+                continue;
+            }
+            let data = asset_data.get(&order.asset).ok_or(Error::AssetNotFound)?;
+            let max_sz = max_trade_sz(data, order.is_buy)?;
+            if order.sz <= max_sz {
+                continue;
+            }
+
+            let sz_decimals = asset_registry.sz_decimals(asset)?;
+            let factor = 10f64.powi(sz_decimals as i32);
+            let clamped = (max_sz * factor).floor() / factor;
+            if self == MarginCheck::Reject || clamped <= 0.0 {
+                return Err(Error::OrderSizeExceeded {
+                    coin: order.asset.clone(),
+                    sz: order.sz,
+                    max_sz,
+                });
+            }
+            tracing::debug!(
+                "Clamping {} order size from {} to {clamped}",
+                order.asset,
+                order.sz
+            );
+            order.sz = clamped;
+        }
+
+        Ok(())
+    }
+}
+
+/// Coins of `orders` that [`MarginCheck::apply`] needs active asset data for.
+pub(crate) fn checked_coins(
+    orders: &[ClientOrderRequest],
+    asset_registry: &AssetRegistry,
+) -> Vec<String> {
+    let mut coins: Vec<String> = orders
+        .iter()
+        .filter(|order| !order.reduce_only)
+        .filter(|order| {
+            asset_registry
+                .asset(&order.asset)
+                .is_ok_and(|asset| !asset.is_spot())
+        })
+        .map(|order| order.asset.clone())
+        .collect();
+    coins.sort();
+    coins.dedup();
+    coins
+}
+
+/// Checks a leverage update against the asset's maximum leverage and margin mode.
+pub(crate) fn validate_leverage(
+    asset_registry: &AssetRegistry,
+    coin: &str,
+    leverage: u32,
+    is_cross: bool,
+) -> Result<()> {
+    let meta = asset_registry.asset_meta(asset_registry.asset(coin)?)?;
+    if leverage == 0 || leverage > meta.max_leverage {
+        return Err(Error::InvalidLeverage {
+            coin: coin.to_string(),
+            leverage,
+            max_leverage: meta.max_leverage,
+        });
+    }
+    if is_cross && meta.only_isolated {
+        return Err(Error::OnlyIsolated(coin.to_string()));
+    }
+
+    Ok(())
+}
+
+/// Converts a USDC amount to the raw `ntli` of an isolated margin update (6 decimals).
+pub(crate) fn usdc_to_ntli(usdc: f64) -> Result<i64> {
+    let ntli = (usdc * 1_000_000.0).round();
+    if !ntli.is_finite() || ntli == 0.0 {
+        return Err(Error::InvalidMarginAmount(usdc));
+    }
+
+    Ok(ntli as i64)
+}
+
+fn max_trade_sz(data: &ActiveAssetDataData, is_buy: bool) -> Result<f64> {
+    let side = if is_buy { 0 } else { 1 };
+    let max_sz = data
+        .max_trade_szs
+        .get(side)
+        .ok_or_else(|| Error::GenericParse("missing max trade size".to_string()))?;
+
+    max_sz.parse::<f64>().map_err(|_| Error::FloatStringParse)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ClientLimit, ClientOrder, LimitTif, Meta, SpotMeta};
+
+    fn registry() -> AssetRegistry {
+        let meta: Meta = serde_json::from_str(
+            r#"{"universe":[{"name":"BTC","szDecimals":5,"maxLeverage":40},{"name":"ISO","szDecimals":1,"maxLeverage":3,"onlyIsolated":true}]}"#,
+        )
+        .unwrap();
+        let spot_meta: SpotMeta = serde_json::from_str(r#"{"universe":[],"tokens":[]}"#).unwrap();
+        AssetRegistry::new(meta, spot_meta)
+    }
+
+    fn order(is_buy: bool, sz: f64) -> ClientOrderRequest {
+        ClientOrderRequest {
+            asset: "BTC".to_string(),
+            is_buy,
+            reduce_only: false,
+            limit_px: 100000.0,
+            sz,
+            cloid: None,
+            order_type: ClientOrder::Limit(ClientLimit { tif: LimitTif::Gtc }),
+        }
+    }
+
+    fn asset_data() -> HashMap<String, ActiveAssetDataData> {
+        let data: ActiveAssetDataData = serde_json::from_str(
+            r#"{"user":"0x0000000000000000000000000000000000000000","coin":"BTC","leverage":{"type":"cross","value":20},"maxTradeSzs":["0.123456","0.5"],"availableToTrade":["1000.0","1000.0"]}"#,
+        )
+        .unwrap();
+        HashMap::from([("BTC".to_string(), data)])
+    }
+
+    #[test]
+    fn margin_check_test() {
+        let registry = registry();
+        let data = asset_data();
+
+        let mut orders = [order(true, 0.2), order(false, 0.2)];
+        assert!(matches!(
+            MarginCheck::Reject.apply(&mut orders, &data, &registry),
+            Err(Error::OrderSizeExceeded { .. })
+        ));
+
+        MarginCheck::Clamp
+            .apply(&mut orders, &data, &registry)
+            .unwrap();
+        assert_eq!(orders[0].sz, 0.12345);
+        assert_eq!(orders[1].sz, 0.2);
+    }
+
+    #[test]
+    fn leverage_validation_test() {
+        let registry = registry();
+
+        assert!(validate_leverage(&registry, "BTC", 40, true).is_ok());
+        assert!(validate_leverage(&registry, "BTC", 41, true).is_err());
+        assert!(validate_leverage(&registry, "BTC", 0, false).is_err());
+        assert!(matches!(
+            validate_leverage(&registry, "ISO", 3, true),
+            Err(Error::OnlyIsolated(_))
+        ));
+    }
+}
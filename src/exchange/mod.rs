@@ -1,20 +1,46 @@
 mod actions;
+mod agent;
 mod api;
+mod bracket;
 mod builder;
 mod cancel;
 mod client;
+mod heartbeat;
+mod kill_switch;
+mod margin_check;
 mod modify;
 mod order;
+mod pipeline;
+mod requote;
 mod response;
+mod risk;
+mod signer;
+mod spot_deploy;
+mod twap;
 
 pub use actions::*;
+pub use agent::{load_agent_key, save_agent_key};
 pub use api::*;
+pub use bracket::{BracketOrderStatuses, ClientBracketOrder, ClientPositionTpsl};
 pub use builder::*;
 pub use cancel::{ClientCancelRequest, ClientCancelRequestCloid};
 pub use client::*;
-pub use modify::{ClientModifyRequest, ModifyRequest};
+pub use heartbeat::ScheduleCancelHeartbeat;
+pub use kill_switch::{CancelFilter, CancelledOrder, ClosedPosition};
+pub use margin_check::MarginCheck;
+pub use modify::{ClientModifyRequest, ModifyId, ModifyRequest};
 pub use order::{
-    BuilderInfo, ClientLimit, ClientOrder, ClientOrderRequest, ClientTrigger, Cloid, LimitTif,
-    MarketCloseParams, MarketOrderParams, Order, TriggerTpsl,
+    BuilderInfo, ClientLimit, ClientOrder, ClientOrderRequest, ClientTrigger, Cloid,
+    CloidGenerator, LimitTif, MarketCloseParams, MarketOrderParams, Order, TriggerTpsl,
+};
+pub use requote::{
+    DesiredQuote, RequoteError, RequoteResult, RequoteStage, RequotedOrder, RestingQuote,
 };
 pub use response::*;
+pub use risk::{RiskLimits, RiskViolation};
+pub use signer::OfflineSigner;
+pub use spot_deploy::{
+    Genesis, RegisterHyperliquidity, RegisterSpot, RegisterToken2, SetDeployerTradingFeeShare,
+    SpotDeploy, TokenSpec, UserGenesis,
+};
+pub use twap::ClientTwapRequest;
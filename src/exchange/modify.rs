@@ -1,19 +1,92 @@
-use super::{ClientOrderRequest, order::OrderRequest};
+use super::{ClientOrderRequest, Cloid, order::OrderRequest};
+use crate::errors::Result;
 
-#[derive(serde::Serialize, Debug, Clone)]
+/// Order to modify, sent as the bare oid or cloid.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+#[serde(untagged)]
 pub enum ModifyId {
     Oid(u64),
     Cloid(String),
 }
 
+impl ModifyId {
+    /// Wire format of the id, with a cloid checked and normalized to lowercase hex.
+    pub(crate) fn to_wire(&self) -> Result<ModifyId> {
+        match self {
+            ModifyId::Oid(oid) => Ok(ModifyId::Oid(*oid)),
+            ModifyId::Cloid(cloid) => Cloid::String(cloid.clone()).to_wire().map(ModifyId::Cloid),
+        }
+    }
+}
+
+impl From<Cloid> for ModifyId {
+    fn from(cloid: Cloid) -> Self {
+        ModifyId::Cloid(cloid.to_string())
+    }
+}
+
 #[derive(Debug)]
 pub struct ClientModifyRequest {
     pub id: ModifyId,
     pub order: ClientOrderRequest,
 }
 
-#[derive(serde::Serialize, Debug, Clone)]
+impl ClientModifyRequest {
+    pub fn by_oid(oid: u64, order: ClientOrderRequest) -> Self {
+        Self {
+            id: ModifyId::Oid(oid),
+            order,
+        }
+    }
+
+    /// Modifies the order tagged with `cloid`, which is validated when the request is sent.
+    pub fn by_cloid(cloid: Cloid, order: ClientOrderRequest) -> Self {
+        Self {
+            id: cloid.into(),
+            order,
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 pub struct ModifyRequest {
+    #[serde(rename = "oid")]
     pub id: ModifyId,
     pub order: OrderRequest,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LimitTif, Order, exchange::order::Limit};
+
+    #[test]
+    fn modify_request_wire_format_test() {
+        let order = OrderRequest {
+            asset: 1,
+            is_buy: true,
+            limit_px: "2000.0".to_string(),
+            sz: "3.5".to_string(),
+            reduce_only: false,
+            order_type: Order::Limit(Limit { tif: LimitTif::Gtc }),
+            cloid: None,
+        };
+
+        // The exchange expects the bare id under "oid", for an oid as well as a cloid
+        let by_oid = serde_json::to_value(ModifyRequest {
+            id: ModifyId::Oid(123),
+            order: order.clone(),
+        })
+        .unwrap();
+        assert_eq!(by_oid["oid"], 123);
+        assert!(by_oid.get("id").is_none());
+
+        let cloid = "0x1e60610f0b3d420597c88c1fed2ad5ee";
+        let by_cloid = serde_json::to_value(ModifyRequest {
+            id: ModifyId::Cloid(cloid.to_string()),
+            order,
+        })
+        .unwrap();
+        assert_eq!(by_cloid["oid"], cloid);
+    }
+}
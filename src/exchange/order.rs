@@ -1,38 +1,71 @@
 use crate::{
+    asset_registry::AssetRegistry,
     errors::Error,
     helpers::{float_to_string_for_hashing, uuid_to_hex_string},
     errors::Result,
 };
 
-#[derive(Default, serde::Serialize, Debug, Clone)]
+/// Highest builder fee allowed on perp orders, in tenths of a basis point (0.1%).
+pub(crate) const MAX_PERP_BUILDER_FEE: u64 = 100;
+/// Highest builder fee allowed on spot orders, in tenths of a basis point (1%).
+pub(crate) const MAX_SPOT_BUILDER_FEE: u64 = 1000;
+
+#[derive(Default, serde::Serialize, serde::Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct BuilderInfo {
     #[serde(rename = "b")]
     pub builder: String,
+    /// Fee in tenths of a basis point, e.g. `10` is 1 bps
     #[serde(rename = "f")]
     pub fee: u64,
 }
 
-#[derive(serde::Serialize, Debug, Clone)]
+impl BuilderInfo {
+    /// Checks the fee against the exchange limit and the fee `approved` by the user,
+    /// both in tenths of a basis point.
+    pub(crate) fn validate_fee(&self, is_spot: bool, approved: u64) -> Result<()> {
+        let max_fee = if is_spot {
+            MAX_SPOT_BUILDER_FEE
+        } else {
+            MAX_PERP_BUILDER_FEE
+        };
+        if self.fee > max_fee {
+            return Err(Error::BuilderFee(format!(
+                "fee of {} tenths of a bps is above the maximum of {max_fee}",
+                self.fee
+            )));
+        }
+        if self.fee > approved {
+            return Err(Error::BuilderFee(format!(
+                "fee of {} tenths of a bps is above the {approved} approved for builder {}",
+                self.fee, self.builder
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 pub enum LimitTif {
     Alo,
     Ioc,
     Gtc,
 }
 
-#[derive(serde::Serialize, Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum TriggerTpsl {
     Tp,
     Sl,
 }
 
-#[derive(serde::Serialize, Clone, Debug)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
 pub struct Limit {
     pub tif: LimitTif,
 }
 
-#[derive(serde::Serialize, Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Trigger {
     pub is_market: bool,
@@ -40,14 +73,14 @@ pub struct Trigger {
     pub tpsl: TriggerTpsl,
 }
 
-#[derive(serde::Serialize, Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub enum Order {
     Limit(Limit),
     Trigger(Trigger),
 }
 
-#[derive(serde::Serialize, Debug, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct OrderRequest {
     #[serde(rename = "a", alias = "asset")]
@@ -66,12 +99,12 @@ pub struct OrderRequest {
     pub cloid: Option<String>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ClientLimit {
     pub tif: LimitTif,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ClientTrigger {
     pub is_market: bool,
     pub trigger_px: f64,
@@ -99,19 +132,139 @@ pub struct MarketCloseParams<'a> {
     pub wallet: Option<&'a ethers::signers::LocalWallet>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum ClientOrder {
     Limit(ClientLimit),
     Trigger(ClientTrigger),
 }
 
+/// Client order id, a 128-bit value sent as `0x` followed by 32 hex digits.
+///
+/// Cloids compare and hash by their 128-bit value, so a [`Cloid::Uuid`] equals the
+/// [`Cloid::String`] of its hex, in any case. Malformed strings compare as strings.
 #[derive(Debug, Clone)]
 pub enum Cloid {
     Uuid(uuid::Uuid),
+    /// Hex string, checked when the order is converted to its wire format
     String(String),
 }
 
+impl Cloid {
+    /// Wire format of the cloid, failing with [`Error::InvalidCloid`] if it is not 16 bytes of hex.
+    pub fn to_wire(&self) -> Result<String> {
+        match self {
+            Cloid::Uuid(uuid) => Ok(uuid_to_hex_string(*uuid)),
+            Cloid::String(cloid) => Ok(uuid_to_hex_string(parse_hex_cloid(cloid)?)),
+        }
+    }
+
+    /// The cloid as 128 bits, failing with [`Error::InvalidCloid`] if it is malformed.
+    pub fn to_uuid(&self) -> Result<uuid::Uuid> {
+        match self {
+            Cloid::Uuid(uuid) => Ok(*uuid),
+            Cloid::String(cloid) => parse_hex_cloid(cloid),
+        }
+    }
+
+    /// Value compared and hashed: the 128 bits, or the string itself when malformed.
+    fn key(&self) -> std::result::Result<uuid::Uuid, &str> {
+        match self {
+            Cloid::Uuid(uuid) => Ok(*uuid),
+            Cloid::String(cloid) => parse_hex_cloid(cloid).map_err(|_| cloid.as_str()),
+        }
+    }
+}
+
+impl PartialEq for Cloid {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Cloid {}
+
+impl std::hash::Hash for Cloid {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
+fn parse_hex_cloid(cloid: &str) -> Result<uuid::Uuid> {
+    let hex = cloid
+        .strip_prefix("0x")
+        .ok_or_else(|| Error::InvalidCloid(cloid.to_string()))?;
+    if hex.len() != 32 || !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return Err(Error::InvalidCloid(cloid.to_string()));
+    }
+    u128::from_str_radix(hex, 16)
+        .map(uuid::Uuid::from_u128)
+        .map_err(|_| Error::InvalidCloid(cloid.to_string()))
+}
+
+impl From<uuid::Uuid> for Cloid {
+    fn from(uuid: uuid::Uuid) -> Self {
+        Cloid::Uuid(uuid)
+    }
+}
+
+impl std::str::FromStr for Cloid {
+    type Err = Error;
+
+    /// Parses either a UUID (`1e60610f-0b3d-4205-97c8-8c1fed2ad5ee`) or a hex cloid
+    /// (`0x1e60610f0b3d420597c88c1fed2ad5ee`).
+    fn from_str(s: &str) -> Result<Self> {
+        if s.starts_with("0x") {
+            return parse_hex_cloid(s).map(Cloid::Uuid);
+        }
+        uuid::Uuid::parse_str(s)
+            .map(Cloid::Uuid)
+            .map_err(|_| Error::InvalidCloid(s.to_string()))
+    }
+}
+
+/// Deterministic cloids made of a 64-bit `prefix` followed by a 64-bit counter.
+///
+/// A strategy can tag each order with [`CloidGenerator::next_cloid`] and later match
+/// fills and cancels back to it with [`CloidGenerator::counter_of`], without waiting
+/// for the exchange to assign an oid.
 #[derive(Debug)]
+pub struct CloidGenerator {
+    prefix: u64,
+    counter: std::sync::atomic::AtomicU64,
+}
+
+impl CloidGenerator {
+    pub fn new(prefix: u64) -> Self {
+        Self::starting_at(prefix, 0)
+    }
+
+    /// Resumes numbering at `counter`, e.g. after a restart.
+    pub fn starting_at(prefix: u64, counter: u64) -> Self {
+        Self {
+            prefix,
+            counter: std::sync::atomic::AtomicU64::new(counter),
+        }
+    }
+
+    pub fn prefix(&self) -> u64 {
+        self.prefix
+    }
+
+    pub fn next_cloid(&self) -> Cloid {
+        let counter = self
+            .counter
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        Cloid::Uuid(uuid::Uuid::from_u64_pair(self.prefix, counter))
+    }
+
+    /// Counter of a cloid made by a generator with the same prefix, or `None` for other cloids.
+    pub fn counter_of(&self, cloid: &Cloid) -> Option<u64> {
+        let (prefix, counter) = cloid.to_uuid().ok()?.as_u64_pair();
+        (prefix == self.prefix).then_some(counter)
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct ClientOrderRequest {
     pub asset: String,
     pub is_buy: bool,
@@ -123,10 +276,8 @@ pub struct ClientOrderRequest {
 }
 
 impl ClientOrderRequest {
-    pub(crate) fn to_order_request(
-        &self,
-        coin_to_asset: &scc::HashMap<String, u32>,
-    ) -> Result<OrderRequest> {
+    /// Wire format of the order, with the asset resolved through `asset_registry`.
+    pub fn to_order_request(&self, asset_registry: &AssetRegistry) -> Result<OrderRequest> {
         let order_type = match &self.order_type {
             ClientOrder::Limit(limit) => Order::Limit(Limit {
                 tif: limit.tif.clone(),
@@ -137,15 +288,9 @@ impl ClientOrderRequest {
                 tpsl: trigger.tpsl.clone(),
             }),
         };
-        let asset = coin_to_asset
-            .read(&self.asset, |_, asset| *asset)
-            .ok_or(Error::AssetNotFound)?;
-
-        let cloid = match &self.cloid {
-            Some(Cloid::Uuid(uuid)) => Some(uuid_to_hex_string(*uuid)),
-            Some(Cloid::String(cloid)) => Some(cloid.clone()),
-            None => None,
-        };
+        let asset = asset_registry.asset(&self.asset)?.to_wire();
+
+        let cloid = self.cloid.as_ref().map(Cloid::to_wire).transpose()?;
 
         Ok(OrderRequest {
             asset,
@@ -191,4 +336,66 @@ impl std::fmt::Display for Cloid {
             Cloid::String(cloid) => write!(f, "{}", cloid),
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder_fee_validation_test() {
+        let builder = BuilderInfo {
+            builder: "0x1ab189b7801140900c711e458212f9c76f8dac79".to_string(),
+            fee: 150,
+        };
+
+        assert!(builder.validate_fee(false, 1000).is_err());
+        assert!(builder.validate_fee(true, 100).is_err());
+        assert!(builder.validate_fee(true, 150).is_ok());
+    }
+
+    #[test]
+    fn cloid_validation_test() -> Result<()> {
+        let uuid: Cloid = "1e60610f-0b3d-4205-97c8-8c1fed2ad5ee".parse()?;
+        let hex = Cloid::String("0x1E60610F0B3D420597C88C1FED2AD5EE".to_string());
+        assert_eq!(uuid.to_wire()?, "0x1e60610f0b3d420597c88c1fed2ad5ee");
+        assert_eq!(hex.to_wire()?, uuid.to_wire()?);
+        assert_eq!(hex, uuid);
+        let cloids: std::collections::HashSet<Cloid> = [uuid, hex].into_iter().collect();
+        assert_eq!(cloids.len(), 1);
+        assert_ne!(
+            Cloid::String("not-a-cloid".to_string()),
+            Cloid::String("NOT-A-CLOID".to_string())
+        );
+
+        for invalid in [
+            "1e60610f0b3d420597c88c1fed2ad5ee",
+            "0x1e60610f0b3d420597c88c1fed2ad5",
+            "0x1e60610f0b3d420597c88c1fed2ad5ee00",
+            "0x+e60610f0b3d420597c88c1fed2ad5ee",
+            "0xzz60610f0b3d420597c88c1fed2ad5ee",
+        ] {
+            assert!(matches!(
+                Cloid::String(invalid.to_string()).to_wire(),
+                Err(Error::InvalidCloid(_))
+            ));
+        }
+        assert!("not-a-cloid".parse::<Cloid>().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn cloid_generator_test() -> Result<()> {
+        let generator = CloidGenerator::starting_at(0xabcd, 7);
+        let first = generator.next_cloid();
+        let second = generator.next_cloid();
+        assert_eq!(first.to_wire()?, "0x000000000000abcd0000000000000007");
+        assert_eq!(generator.counter_of(&second), Some(8));
+
+        let parsed = Cloid::String(second.to_wire()?);
+        assert_eq!(generator.counter_of(&parsed), Some(8));
+        assert_eq!(CloidGenerator::new(1).counter_of(&first), None);
+
+        Ok(())
+    }
+}
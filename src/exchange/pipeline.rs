@@ -0,0 +1,740 @@
+use crate::{
+    AssetRegistry, BulkCancelCloid, Error, ExchangeResponseStatus, OpenOrdersResponse,
+    exchange::{
+        ClientCancelRequest, ClientOrderRequest,
+        actions::{
+            ApproveAgent, ApproveBuilderFee, BulkCancel, BulkModify, BulkOrder, Grouping,
+            ScheduleCancel, SetReferrer, UpdateIsolatedMargin, UpdateLeverage, UsdSend,
+        },
+        cancel::{CancelRequest, CancelRequestCloid},
+        modify::{ClientModifyRequest, ModifyRequest},
+    },
+    helpers::next_nonce,
+    info::client::InfoClient,
+    req::{Endpoint, HttpClient},
+    errors::Result,
+};
+use crate::{
+    CDeposit, CWithdraw, ClassTransfer, CreateSubAccount, CreateSubAccountResult, CreateVault,
+    CreateVaultResult, SpotDeploy, SpotSend, SpotUser, SubAccountSpotTransfer,
+    SubAccountUsdTransfer, TokenDelegate, TwapCancel, TwapOrder, TwapResult, VaultDistribute,
+    VaultModify, VaultTransfer, Withdraw3,
+};
+
+use ethers::{signers::LocalWallet, types::H160};
+use serde::de::DeserializeOwned;
+
+use super::api::{Actions, ExchangePayload};
+use super::kill_switch::CancelFilter;
+use super::margin_check::{usdc_to_ntli, validate_leverage};
+use super::order::{BuilderInfo, LimitTif, MarketCloseParams, MarketOrderParams};
+use super::signer::sign_action;
+use super::twap::ClientTwapRequest;
+use super::{ClientLimit, ClientOrder};
+
+/// Posts a signed payload to the exchange endpoint and parses the response, with an error
+/// status returned as [`Error::Exchange`].
+pub(crate) async fn post_payload<T: DeserializeOwned>(
+    http_client: &HttpClient,
+    payload: &ExchangePayload,
+) -> Result<T> {
+    let res = serde_json::to_string(payload).map_err(|e| Error::JsonParse(e.to_string()))?;
+    tracing::debug!("Sending request {res:?}");
+
+    let output = &http_client
+        .post(Endpoint::Exchange, res)
+        .await
+        .map_err(|e| Error::JsonParse(e.to_string()))?;
+
+    serde_json::from_str::<ExchangeResponseStatus<T>>(output)
+        .map_err(|e| Error::JsonParse(e.to_string()))?
+        .into_result()
+}
+
+/// Action pipeline shared by [`crate::ExchangeClient`] and [`crate::ExchangeApi`].
+///
+/// Builds each action, hashes and signs it with `wallet`, posts it and parses the response.
+/// L1 actions are sent for `vault_address`, except the ones only the master account can
+/// send. User signed actions are never sent for a vault.
+pub(crate) struct ExchangeCore<'a> {
+    pub(crate) http_client: &'a HttpClient,
+    pub(crate) asset_registry: &'a AssetRegistry,
+    pub(crate) wallet: &'a LocalWallet,
+    pub(crate) vault_address: Option<H160>,
+    /// Chain id user signed actions are signed for
+    pub(crate) signature_chain_id: u64,
+}
+
+impl ExchangeCore<'_> {
+    pub(crate) fn with_vault(self, vault_address: Option<H160>) -> Self {
+        Self {
+            vault_address,
+            ..self
+        }
+    }
+
+    fn info_client(&self) -> InfoClient {
+        InfoClient::builder()
+            .http_client(self.http_client.client.clone())
+            .network(self.http_client.network_type())
+            .build()
+    }
+
+    fn hyperliquid_chain(&self) -> String {
+        if self.http_client.is_mainnet() {
+            "Mainnet".to_string()
+        } else {
+            "Testnet".to_string()
+        }
+    }
+
+    /// Signs `action` and sends it. L1 actions are signed for `vault_address`.
+    async fn send_action<T: DeserializeOwned>(
+        &self,
+        action: Actions,
+        nonce: u64,
+        vault_address: Option<H160>,
+    ) -> Result<T> {
+        let payload = sign_action(
+            self.wallet,
+            action,
+            nonce,
+            vault_address,
+            self.http_client.is_mainnet(),
+        )?;
+        post_payload(self.http_client, &payload).await
+    }
+
+    pub(crate) async fn usdc_transfer<T: DeserializeOwned>(
+        &self,
+        amount: &str,
+        destination: &str,
+    ) -> Result<T> {
+        let timestamp = next_nonce();
+        let action = Actions::UsdSend(UsdSend {
+            signature_chain_id: self.signature_chain_id.into(),
+            hyperliquid_chain: self.hyperliquid_chain(),
+            destination: destination.to_string(),
+            amount: amount.to_string(),
+            time: timestamp,
+        });
+
+        self.send_action(action, timestamp, None).await
+    }
+
+    pub(crate) async fn class_transfer<T: DeserializeOwned>(
+        &self,
+        usdc: f64,
+        to_perp: bool,
+    ) -> Result<T> {
+        // payload expects usdc without decimals
+        let usdc = (usdc * 1e6).round() as u64;
+        let action = Actions::SpotUser(SpotUser {
+            class_transfer: ClassTransfer { usdc, to_perp },
+        });
+
+        self.send_action(action, next_nonce(), self.vault_address)
+            .await
+    }
+
+    pub(crate) async fn vault_transfer<T: DeserializeOwned>(
+        &self,
+        vault_address: H160,
+        is_deposit: bool,
+        usd: u64,
+    ) -> Result<T> {
+        let action = Actions::VaultTransfer(VaultTransfer {
+            vault_address,
+            is_deposit,
+            usd,
+        });
+
+        self.send_action(action, next_nonce(), self.vault_address)
+            .await
+    }
+
+    pub(crate) async fn create_vault(
+        &self,
+        name: String,
+        description: String,
+        initial_usd: u64,
+    ) -> Result<CreateVaultResult> {
+        let nonce = next_nonce();
+        let action = Actions::CreateVault(CreateVault {
+            name,
+            description,
+            initial_usd,
+            nonce,
+        });
+
+        // Vault management is always done by the leader account
+        self.send_action(action, nonce, None).await
+    }
+
+    pub(crate) async fn vault_modify<T: DeserializeOwned>(
+        &self,
+        vault_address: H160,
+        allow_deposits: Option<bool>,
+        always_close_on_withdraw: Option<bool>,
+    ) -> Result<T> {
+        let action = Actions::VaultModify(VaultModify {
+            vault_address,
+            allow_deposits,
+            always_close_on_withdraw,
+        });
+
+        self.send_action(action, next_nonce(), None).await
+    }
+
+    pub(crate) async fn vault_distribute<T: DeserializeOwned>(
+        &self,
+        vault_address: H160,
+        usd: u64,
+    ) -> Result<T> {
+        let action = Actions::VaultDistribute(VaultDistribute { vault_address, usd });
+
+        self.send_action(action, next_nonce(), None).await
+    }
+
+    /// IOC order opening a position at `slippage` (default 5%) from the mid or `params.px`.
+    pub(crate) async fn market_open_order(
+        &self,
+        params: MarketOrderParams<'_>,
+    ) -> Result<ClientOrderRequest> {
+        let slippage = params.slippage.unwrap_or(0.05); // Default 5% slippage
+        let (px, sz_decimals) = self
+            .calculate_slippage_price(params.asset, params.is_buy, slippage, params.px)
+            .await?;
+
+        Ok(ClientOrderRequest {
+            asset: params.asset.to_string(),
+            is_buy: params.is_buy,
+            reduce_only: false,
+            limit_px: px,
+            sz: round_to_decimals(params.sz, sz_decimals),
+            cloid: params.cloid,
+            order_type: ClientOrder::Limit(ClientLimit { tif: LimitTif::Ioc }),
+        })
+    }
+
+    /// Reduce only IOC order closing the position of `user`, or `params.sz` of it.
+    pub(crate) async fn market_close_order(
+        &self,
+        params: MarketCloseParams<'_>,
+        user: H160,
+    ) -> Result<ClientOrderRequest> {
+        let slippage = params.slippage.unwrap_or(0.05); // Default 5% slippage
+
+        let info_client = self.info_client();
+        let asset = self.asset_registry.asset(params.asset)?;
+        let user_state = match self.asset_registry.perp_dex_name(asset) {
+            Some(dex) => info_client.perp_dex_user_state(user, dex).await?,
+            None => info_client.user_state(user).await?,
+        };
+
+        let position = user_state
+            .asset_positions
+            .iter()
+            .find(|p| p.position.coin == params.asset)
+            .ok_or(Error::AssetNotFound)?;
+
+        let szi = position
+            .position
+            .szi
+            .parse::<f64>()
+            .map_err(|_| Error::FloatStringParse)?;
+
+        let (px, sz_decimals) = self
+            .calculate_slippage_price(params.asset, szi < 0.0, slippage, params.px)
+            .await?;
+
+        let sz = round_to_decimals(params.sz.unwrap_or_else(|| szi.abs()), sz_decimals);
+
+        Ok(ClientOrderRequest {
+            asset: params.asset.to_string(),
+            is_buy: szi < 0.0,
+            reduce_only: true,
+            limit_px: px,
+            sz,
+            cloid: params.cloid,
+            order_type: ClientOrder::Limit(ClientLimit { tif: LimitTif::Ioc }),
+        })
+    }
+
+    /// Reduce only IOC orders closing every perp position of `user` at 5% slippage from
+    /// the mid, with the coin and signed size of each position.
+    pub(crate) async fn flatten_orders(
+        &self,
+        user: H160,
+    ) -> Result<Vec<(String, f64, ClientOrderRequest)>> {
+        let info_client = self.info_client();
+        let mut user_states = vec![info_client.user_state(user).await?];
+        let mut all_mids = info_client.all_mids().await?;
+        for dex in self.asset_registry.perp_dex_names() {
+            user_states.push(info_client.perp_dex_user_state(user, dex).await?);
+            all_mids.extend(info_client.perp_dex_all_mids(dex).await?);
+        }
+
+        let mut orders = Vec::new();
+        for position in user_states.iter().flat_map(|state| &state.asset_positions) {
+            let coin = &position.position.coin;
+            let szi = position
+                .position
+                .szi
+                .parse::<f64>()
+                .map_err(|_| Error::FloatStringParse)?;
+            if szi == 0.0 {
+                continue;
+            }
+
+            let asset = self.asset_registry.asset(coin)?;
+            let mid = all_mids
+                .get(self.asset_registry.name(asset)?)
+                .ok_or(Error::AssetNotFound)?
+                .parse::<f64>()
+                .map_err(|_| Error::FloatStringParse)?;
+            let (px, sz_decimals) = self
+                .calculate_slippage_price(coin, szi < 0.0, 0.05, Some(mid))
+                .await?;
+
+            let order = ClientOrderRequest {
+                asset: coin.clone(),
+                is_buy: szi < 0.0,
+                reduce_only: true,
+                limit_px: px,
+                sz: round_to_decimals(szi.abs(), sz_decimals),
+                cloid: None,
+                order_type: ClientOrder::Limit(ClientLimit { tif: LimitTif::Ioc }),
+            };
+            orders.push((coin.clone(), szi, order));
+        }
+
+        Ok(orders)
+    }
+
+    /// Open orders of `user` on the default perp dex, spot and the registered perp dexes.
+    pub(crate) async fn open_orders(&self, user: H160) -> Result<Vec<OpenOrdersResponse>> {
+        let info_client = self.info_client();
+        let mut open_orders = info_client.open_orders(user).await?;
+        for dex in self.asset_registry.perp_dex_names() {
+            open_orders.extend(info_client.perp_dex_open_orders(user, dex).await?);
+        }
+        Ok(open_orders)
+    }
+
+    /// Cancels of the open orders of `user` matching `filter`, on the default perp dex,
+    /// spot and the registered perp dexes.
+    pub(crate) async fn cancel_all_requests(
+        &self,
+        user: H160,
+        filter: &CancelFilter,
+    ) -> Result<Vec<ClientCancelRequest>> {
+        Ok(self
+            .open_orders(user)
+            .await?
+            .into_iter()
+            .filter(|order| filter.matches(order))
+            .map(|order| ClientCancelRequest {
+                asset: order.coin,
+                oid: order.oid,
+            })
+            .collect())
+    }
+
+    async fn calculate_slippage_price(
+        &self,
+        asset: &str,
+        is_buy: bool,
+        slippage: f64,
+        px: Option<f64>,
+    ) -> Result<(f64, u32)> {
+        let asset = self.asset_registry.asset(asset)?;
+        let sz_decimals = self.asset_registry.sz_decimals(asset)?;
+        let price_decimals = self.asset_registry.price_decimals(asset)?;
+
+        let px = if let Some(px) = px {
+            px
+        } else {
+            let info_client = self.info_client();
+            let all_mids = match self.asset_registry.perp_dex_name(asset) {
+                Some(dex) => info_client.perp_dex_all_mids(dex).await?,
+                None => info_client.all_mids().await?,
+            };
+            all_mids
+                .get(self.asset_registry.name(asset)?)
+                .ok_or(Error::AssetNotFound)?
+                .parse::<f64>()
+                .map_err(|_| Error::FloatStringParse)?
+        };
+
+        tracing::debug!("px before slippage: {px:?}");
+        let slippage_factor = if is_buy {
+            1.0 + slippage
+        } else {
+            1.0 - slippage
+        };
+        let px = px * slippage_factor;
+
+        // Round to the correct number of decimal places and significant figures
+        let px = round_to_significant_and_decimal(px, 5, price_decimals);
+
+        tracing::debug!("px after slippage: {px:?}");
+        Ok((px, sz_decimals))
+    }
+
+    /// Checks that the fee of `builder` is within the exchange limits for `orders` and that
+    /// `user` approved at least that fee for the builder.
+    pub(crate) async fn check_builder_fee(
+        &self,
+        user: H160,
+        builder: &BuilderInfo,
+        orders: &[ClientOrderRequest],
+    ) -> Result<()> {
+        let builder_address = builder
+            .builder
+            .parse::<H160>()
+            .map_err(|e| Error::BuilderFee(e.to_string()))?;
+        let approved = self
+            .info_client()
+            .max_builder_fee(user, builder_address)
+            .await?;
+        let is_spot = orders.iter().all(|order| {
+            self.asset_registry
+                .asset(&order.asset)
+                .is_ok_and(|asset| asset.is_spot())
+        });
+
+        builder.validate_fee(is_spot, approved)
+    }
+
+    pub(crate) async fn bulk_order<T: DeserializeOwned>(
+        &self,
+        orders: &[ClientOrderRequest],
+        grouping: Grouping,
+        builder: Option<BuilderInfo>,
+    ) -> Result<T> {
+        let transformed_orders = orders
+            .iter()
+            .map(|order| order.to_order_request(self.asset_registry))
+            .collect::<Result<Vec<_>>>()?;
+
+        let action = Actions::Order(BulkOrder {
+            orders: transformed_orders,
+            grouping,
+            builder,
+        });
+
+        self.send_action(action, next_nonce(), self.vault_address)
+            .await
+    }
+
+    pub(crate) async fn bulk_cancel<T: DeserializeOwned>(
+        &self,
+        cancels: &[ClientCancelRequest],
+    ) -> Result<T> {
+        let mut transformed_cancels = Vec::new();
+        for cancel in cancels.iter() {
+            let asset = self.asset_registry.asset(&cancel.asset)?.to_wire();
+            transformed_cancels.push(CancelRequest {
+                asset,
+                oid: cancel.oid,
+            });
+        }
+
+        let action = Actions::Cancel(BulkCancel {
+            cancels: transformed_cancels,
+        });
+
+        self.send_action(action, next_nonce(), self.vault_address)
+            .await
+    }
+
+    pub(crate) async fn bulk_modify<T: DeserializeOwned>(
+        &self,
+        modifies: &[ClientModifyRequest],
+    ) -> Result<T> {
+        let mut transformed_modifies = Vec::new();
+        for modify in modifies.iter() {
+            transformed_modifies.push(ModifyRequest {
+                id: modify.id.to_wire()?,
+                order: modify.order.to_order_request(self.asset_registry)?,
+            });
+        }
+
+        let action = Actions::BatchModify(BulkModify {
+            modifies: transformed_modifies,
+        });
+
+        self.send_action(action, next_nonce(), self.vault_address)
+            .await
+    }
+
+    pub(crate) async fn bulk_cancel_by_cloid<T: DeserializeOwned>(
+        &self,
+        cancels: &[super::ClientCancelRequestCloid],
+    ) -> Result<T> {
+        let mut transformed_cancels: Vec<CancelRequestCloid> = Vec::new();
+        for cancel in cancels.iter() {
+            let asset = self.asset_registry.asset(&cancel.asset)?.to_wire();
+            transformed_cancels.push(CancelRequestCloid {
+                asset,
+                cloid: cancel.cloid.to_wire()?,
+            });
+        }
+
+        let action = Actions::CancelByCloid(BulkCancelCloid {
+            cancels: transformed_cancels,
+        });
+
+        self.send_action(action, next_nonce(), self.vault_address)
+            .await
+    }
+
+    pub(crate) async fn twap_order(&self, twap: ClientTwapRequest) -> Result<TwapResult> {
+        let action = Actions::TwapOrder(TwapOrder {
+            twap: twap.to_twap_request(self.asset_registry)?,
+        });
+
+        self.send_action(action, next_nonce(), self.vault_address)
+            .await
+    }
+
+    pub(crate) async fn twap_cancel(&self, coin: &str, twap_id: u64) -> Result<TwapResult> {
+        let action = Actions::TwapCancel(TwapCancel {
+            asset: self.asset_registry.asset(coin)?.to_wire(),
+            twap_id,
+        });
+
+        self.send_action(action, next_nonce(), self.vault_address)
+            .await
+    }
+
+    pub(crate) async fn update_leverage<T: DeserializeOwned>(
+        &self,
+        leverage: u32,
+        coin: &str,
+        is_cross: bool,
+    ) -> Result<T> {
+        validate_leverage(self.asset_registry, coin, leverage, is_cross)?;
+
+        let asset_index = self.asset_registry.asset(coin)?.to_wire();
+        let action = Actions::UpdateLeverage(UpdateLeverage {
+            asset: asset_index,
+            is_cross,
+            leverage,
+        });
+
+        self.send_action(action, next_nonce(), self.vault_address)
+            .await
+    }
+
+    pub(crate) async fn update_isolated_margin<T: DeserializeOwned>(
+        &self,
+        amount: f64,
+        coin: &str,
+    ) -> Result<T> {
+        let amount = usdc_to_ntli(amount)?;
+
+        let asset = self.asset_registry.asset(coin)?;
+        self.asset_registry.asset_meta(asset)?;
+        let action = Actions::UpdateIsolatedMargin(UpdateIsolatedMargin {
+            asset: asset.to_wire(),
+            is_buy: true,
+            ntli: amount,
+        });
+
+        self.send_action(action, next_nonce(), self.vault_address)
+            .await
+    }
+
+    pub(crate) async fn approve_agent<T: DeserializeOwned>(
+        &self,
+        agent_address: H160,
+        agent_name: Option<String>,
+    ) -> Result<T> {
+        let nonce = next_nonce();
+        let action = Actions::ApproveAgent(ApproveAgent {
+            signature_chain_id: self.signature_chain_id.into(),
+            hyperliquid_chain: self.hyperliquid_chain(),
+            agent_address,
+            agent_name,
+            nonce,
+        });
+
+        self.send_action(action, nonce, None).await
+    }
+
+    pub(crate) async fn withdraw_from_bridge<T: DeserializeOwned>(
+        &self,
+        amount: &str,
+        destination: &str,
+    ) -> Result<T> {
+        let timestamp = next_nonce();
+        let action = Actions::Withdraw3(Withdraw3 {
+            signature_chain_id: self.signature_chain_id.into(),
+            hyperliquid_chain: self.hyperliquid_chain(),
+            destination: destination.to_string(),
+            amount: amount.to_string(),
+            time: timestamp,
+        });
+
+        self.send_action(action, timestamp, None).await
+    }
+
+    pub(crate) async fn staking_deposit<T: DeserializeOwned>(&self, wei: u64) -> Result<T> {
+        let timestamp = next_nonce();
+        let action = Actions::CDeposit(CDeposit {
+            signature_chain_id: self.signature_chain_id.into(),
+            hyperliquid_chain: self.hyperliquid_chain(),
+            wei,
+            nonce: timestamp,
+        });
+
+        self.send_action(action, timestamp, None).await
+    }
+
+    pub(crate) async fn staking_withdraw<T: DeserializeOwned>(&self, wei: u64) -> Result<T> {
+        let timestamp = next_nonce();
+        let action = Actions::CWithdraw(CWithdraw {
+            signature_chain_id: self.signature_chain_id.into(),
+            hyperliquid_chain: self.hyperliquid_chain(),
+            wei,
+            nonce: timestamp,
+        });
+
+        self.send_action(action, timestamp, None).await
+    }
+
+    pub(crate) async fn token_delegate<T: DeserializeOwned>(
+        &self,
+        validator: H160,
+        wei: u64,
+        is_undelegate: bool,
+    ) -> Result<T> {
+        let timestamp = next_nonce();
+        let action = Actions::TokenDelegate(TokenDelegate {
+            signature_chain_id: self.signature_chain_id.into(),
+            hyperliquid_chain: self.hyperliquid_chain(),
+            validator,
+            wei,
+            is_undelegate,
+            nonce: timestamp,
+        });
+
+        self.send_action(action, timestamp, None).await
+    }
+
+    pub(crate) async fn spot_transfer<T: DeserializeOwned>(
+        &self,
+        amount: &str,
+        destination: &str,
+        token: &str,
+    ) -> Result<T> {
+        let timestamp = next_nonce();
+        let action = Actions::SpotSend(SpotSend {
+            signature_chain_id: self.signature_chain_id.into(),
+            hyperliquid_chain: self.hyperliquid_chain(),
+            destination: destination.to_string(),
+            amount: amount.to_string(),
+            time: timestamp,
+            token: token.to_string(),
+        });
+
+        self.send_action(action, timestamp, None).await
+    }
+
+    pub(crate) async fn create_sub_account(&self, name: String) -> Result<CreateSubAccountResult> {
+        let action = Actions::CreateSubAccount(CreateSubAccount { name });
+
+        // Sub-account management is always done by the master account
+        self.send_action(action, next_nonce(), None).await
+    }
+
+    pub(crate) async fn sub_account_transfer<T: DeserializeOwned>(
+        &self,
+        sub_account_user: H160,
+        is_deposit: bool,
+        usd: u64,
+    ) -> Result<T> {
+        let action = Actions::SubAccountTransfer(SubAccountUsdTransfer {
+            sub_account_user,
+            is_deposit,
+            usd,
+        });
+
+        self.send_action(action, next_nonce(), None).await
+    }
+
+    pub(crate) async fn sub_account_spot_transfer<T: DeserializeOwned>(
+        &self,
+        sub_account_user: H160,
+        is_deposit: bool,
+        token: &str,
+        amount: &str,
+    ) -> Result<T> {
+        let action = Actions::SubAccountSpotTransfer(SubAccountSpotTransfer {
+            sub_account_user,
+            is_deposit,
+            token: token.to_string(),
+            amount: amount.to_string(),
+        });
+
+        self.send_action(action, next_nonce(), None).await
+    }
+
+    pub(crate) async fn spot_deploy<T: DeserializeOwned>(
+        &self,
+        spot_deploy: SpotDeploy,
+    ) -> Result<T> {
+        // Deployments are always signed by the deployer itself
+        let action = Actions::SpotDeploy(spot_deploy);
+        self.send_action(action, next_nonce(), None).await
+    }
+
+    pub(crate) async fn schedule_cancel<T: DeserializeOwned>(
+        &self,
+        time: Option<u64>,
+    ) -> Result<T> {
+        let action = Actions::ScheduleCancel(ScheduleCancel { time });
+
+        self.send_action(action, next_nonce(), self.vault_address)
+            .await
+    }
+
+    pub(crate) async fn set_referrer<T: DeserializeOwned>(&self, code: String) -> Result<T> {
+        let action = Actions::SetReferrer(SetReferrer { code });
+
+        self.send_action(action, next_nonce(), self.vault_address)
+            .await
+    }
+
+    pub(crate) async fn approve_builder_fee<T: DeserializeOwned>(
+        &self,
+        builder: H160,
+        max_fee_rate: String,
+    ) -> Result<T> {
+        let timestamp = next_nonce();
+        let action = Actions::ApproveBuilderFee(ApproveBuilderFee {
+            signature_chain_id: self.signature_chain_id.into(),
+            hyperliquid_chain: self.hyperliquid_chain(),
+            builder,
+            max_fee_rate,
+            nonce: timestamp,
+        });
+
+        self.send_action(action, timestamp, None).await
+    }
+}
+
+fn round_to_decimals(value: f64, decimals: u32) -> f64 {
+    let factor = 10f64.powi(decimals as i32);
+    (value * factor).round() / factor
+}
+
+fn round_to_significant_and_decimal(value: f64, sig_figs: u32, max_decimals: u32) -> f64 {
+    let abs_value = value.abs();
+    let magnitude = abs_value.log10().floor() as i32;
+    let scale = 10f64.powi(sig_figs as i32 - magnitude - 1);
+    let rounded = (abs_value * scale).round() / scale;
+    round_to_decimals(rounded.copysign(value), max_decimals)
+}
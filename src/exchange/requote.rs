@@ -0,0 +1,221 @@
+use crate::{
+    ClientCancelRequest, ClientLimit, ClientOrder, ClientOrderRequest, Cloid, EPSILON, Error,
+    ExchangeDataStatus, LimitTif, exchange::kill_switch::CancelledOrder,
+};
+
+/// Order resting on the book, as tracked by the caller of [`crate::ExchangeClient::requote`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RestingQuote {
+    pub oid: u64,
+    pub is_buy: bool,
+    pub px: f64,
+    /// Size still resting, after any partial fills
+    pub sz: f64,
+}
+
+/// Order that should be resting on the book after [`crate::ExchangeClient::requote`].
+#[derive(Debug, Clone)]
+pub struct DesiredQuote {
+    pub is_buy: bool,
+    pub px: f64,
+    pub sz: f64,
+    pub tif: LimitTif,
+    pub cloid: Option<Cloid>,
+}
+
+/// Desired quote that was modified or placed by [`crate::ExchangeClient::requote`].
+#[derive(Debug, Clone)]
+pub struct RequotedOrder {
+    pub quote: DesiredQuote,
+    /// Oid of the resting order this quote took over, if any
+    pub replaced_oid: Option<u64>,
+    pub status: ExchangeDataStatus,
+}
+
+impl RequotedOrder {
+    /// Oid the quote now rests under, `None` if it filled right away or was rejected.
+    pub fn resting_oid(&self) -> Option<u64> {
+        match &self.status {
+            ExchangeDataStatus::Resting(order) => Some(order.oid),
+            _ => None,
+        }
+    }
+}
+
+/// Outcome of [`crate::ExchangeClient::requote`], including the requests that went through
+/// before a failed stage.
+#[derive(Debug, Clone, Default)]
+pub struct RequoteResult {
+    /// Oids of the resting orders already matching a desired quote, left untouched
+    pub kept: Vec<u64>,
+    /// Resting orders cancelled because they no longer match a desired quote
+    pub cancelled: Vec<CancelledOrder>,
+    /// Desired quotes sent as a modify or a new order
+    pub requoted: Vec<RequotedOrder>,
+    /// Stage that failed, if any. The stages after it were not sent.
+    pub error: Option<RequoteError>,
+}
+
+impl RequoteResult {
+    pub(crate) fn failed(mut self, stage: RequoteStage, error: Error) -> Self {
+        self.error = Some(RequoteError { stage, error });
+        self
+    }
+}
+
+/// Batch sent by [`crate::ExchangeClient::requote`], in the order they are sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequoteStage {
+    Modify,
+    Cancel,
+    Order,
+}
+
+/// Failure of one stage of [`crate::ExchangeClient::requote`].
+#[derive(thiserror::Error, Debug, Clone)]
+#[error("requote {stage:?} failed: {error}")]
+pub struct RequoteError {
+    pub stage: RequoteStage,
+    pub error: Error,
+}
+
+/// Requests needed to go from the resting orders to the desired quotes.
+#[derive(Debug, Default)]
+pub(crate) struct RequotePlan {
+    pub(crate) kept: Vec<u64>,
+    /// Size decreases at an unchanged price, done in place to keep queue priority
+    pub(crate) modifies: Vec<(u64, DesiredQuote)>,
+    pub(crate) cancels: Vec<u64>,
+    /// New quotes, with the oid of the cancelled order they replace
+    pub(crate) orders: Vec<(Option<u64>, DesiredQuote)>,
+}
+
+impl RequotePlan {
+    /// Matches each desired quote with a resting order on the same side and at the same
+    /// price. Only size decreases are modified: a price change or a size increase loses
+    /// priority anyway, so the resting order is cancelled and the quote placed again.
+    pub(crate) fn new(resting: &[RestingQuote], desired: &[DesiredQuote]) -> Self {
+        let mut plan = RequotePlan::default();
+        let mut matched = vec![false; resting.len()];
+
+        for quote in desired {
+            let found = resting.iter().enumerate().find(|(i, order)| {
+                !matched[*i]
+                    && order.is_buy == quote.is_buy
+                    && (order.px - quote.px).abs() < EPSILON
+            });
+            let Some((i, order)) = found else {
+                plan.orders.push((None, quote.clone()));
+                continue;
+            };
+            matched[i] = true;
+
+            if (order.sz - quote.sz).abs() < EPSILON {
+                plan.kept.push(order.oid);
+            } else if quote.sz < order.sz {
+                plan.modifies.push((order.oid, quote.clone()));
+            } else {
+                plan.cancels.push(order.oid);
+                plan.orders.push((Some(order.oid), quote.clone()));
+            }
+        }
+
+        for (order, matched) in resting.iter().zip(matched) {
+            if !matched {
+                plan.cancels.push(order.oid);
+            }
+        }
+
+        plan
+    }
+
+    pub(crate) fn cancel_requests(&self, asset: &str) -> Vec<ClientCancelRequest> {
+        self.cancels
+            .iter()
+            .map(|oid| ClientCancelRequest {
+                asset: asset.to_string(),
+                oid: *oid,
+            })
+            .collect()
+    }
+}
+
+/// New quotes to place once the cancels went through. A replacement is dropped when its
+/// resting order could not be cancelled, as that usually means the order filled.
+pub(crate) fn placeable_orders(
+    orders: Vec<(Option<u64>, DesiredQuote)>,
+    cancelled: &[CancelledOrder],
+) -> Vec<(Option<u64>, DesiredQuote)> {
+    orders
+        .into_iter()
+        .filter(|(replaced_oid, _)| {
+            replaced_oid.is_none_or(|oid| {
+                cancelled.iter().any(|order| {
+                    order.oid == oid && matches!(order.status, ExchangeDataStatus::Success)
+                })
+            })
+        })
+        .collect()
+}
+
+impl DesiredQuote {
+    pub(crate) fn to_order(&self, asset: &str) -> ClientOrderRequest {
+        ClientOrderRequest {
+            asset: asset.to_string(),
+            is_buy: self.is_buy,
+            reduce_only: false,
+            limit_px: self.px,
+            sz: self.sz,
+            cloid: self.cloid.clone(),
+            order_type: ClientOrder::Limit(ClientLimit {
+                tif: self.tif.clone(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requote_plan_test() {
+        let resting = |oid, is_buy, px, sz| RestingQuote {
+            oid,
+            is_buy,
+            px,
+            sz,
+        };
+        let desired = |is_buy, px, sz| DesiredQuote {
+            is_buy,
+            px,
+            sz,
+            tif: LimitTif::Alo,
+            cloid: None,
+        };
+
+        let plan = RequotePlan::new(
+            &[
+                resting(1, true, 100.0, 1.0),
+                resting(2, true, 99.0, 1.0),
+                resting(3, false, 101.0, 1.0),
+                resting(4, false, 102.0, 1.0),
+                resting(5, false, 103.0, 1.0),
+            ],
+            &[
+                desired(true, 100.0, 1.0),
+                desired(true, 99.0, 0.5),
+                desired(false, 101.0, 2.0),
+                desired(false, 102.5, 1.0),
+                desired(true, 100.0, 1.0),
+            ],
+        );
+
+        assert_eq!(plan.kept, vec![1]);
+        let modifies: Vec<_> = plan.modifies.iter().map(|(oid, q)| (*oid, q.sz)).collect();
+        assert_eq!(modifies, vec![(2, 0.5)]);
+        assert_eq!(plan.cancels, vec![3, 4, 5]);
+        let orders: Vec<_> = plan.orders.iter().map(|(oid, q)| (*oid, q.px)).collect();
+        assert_eq!(orders, vec![(Some(3), 101.0), (None, 102.5), (None, 100.0)]);
+    }
+}
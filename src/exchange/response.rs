@@ -1,5 +1,8 @@
+use ethers::types::H160;
 use serde::Deserialize;
 
+use crate::{Error, errors::Result};
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct RestingOrder {
     pub oid: u64,
@@ -36,10 +39,249 @@ pub struct ExchangeResponse {
     pub data: Option<ExchangeDataStatuses>,
 }
 
+/// Defines the result of an action answered with one status per request.
+macro_rules! statuses_result {
+    ($(#[$meta:meta])* $result:ident) => {
+        $(#[$meta])*
+        #[derive(Deserialize, Debug, Clone)]
+        #[serde(try_from = "ExchangeResponse")]
+        pub struct $result {
+            /// One status per request, in the order of the requests
+            pub statuses: Vec<ExchangeDataStatus>,
+        }
+
+        impl TryFrom<ExchangeResponse> for $result {
+            type Error = Error;
+
+            fn try_from(response: ExchangeResponse) -> Result<Self> {
+                let statuses = response
+                    .data
+                    .ok_or_else(|| Error::GenericParse("missing statuses".to_string()))?
+                    .statuses;
+                Ok(Self { statuses })
+            }
+        }
+
+        impl $result {
+            /// Status of the only request, with a rejection returned as [`Error::Exchange`].
+            pub fn into_status(self) -> Result<ExchangeDataStatus> {
+                match <[ExchangeDataStatus; 1]>::try_from(self.statuses) {
+                    Ok([ExchangeDataStatus::Error(e)]) => Err(Error::Exchange(e)),
+                    Ok([status]) => Ok(status),
+                    Err(statuses) => Err(Error::GenericParse(format!(
+                        "expected 1 status, got {}",
+                        statuses.len()
+                    ))),
+                }
+            }
+
+            /// Statuses of a batch of `expected` requests, checking that there is one per
+            /// request.
+            pub fn into_statuses(self, expected: usize) -> Result<Vec<ExchangeDataStatus>> {
+                if self.statuses.len() != expected {
+                    return Err(Error::GenericParse(format!(
+                        "expected {expected} statuses, got {}",
+                        self.statuses.len()
+                    )));
+                }
+
+                Ok(self.statuses)
+            }
+        }
+    };
+}
+
+statuses_result! {
+    /// Result of placing orders.
+    OrderResult
+}
+
+statuses_result! {
+    /// Result of cancelling orders, by oid or cloid.
+    CancelResult
+}
+
+statuses_result! {
+    /// Result of modifying orders.
+    ModifyResult
+}
+
+/// Result of a transfer, withdrawal or staking balance move, which the exchange only
+/// acknowledges.
+#[derive(Deserialize, Debug, Clone)]
+pub struct TransferResult {}
+
+/// Result of an action the exchange only acknowledges, such as a leverage update or an
+/// approval.
+#[derive(Deserialize, Debug, Clone)]
+pub struct ActionResult {}
+
+/// Response of an action answered with a single data value.
+#[derive(Deserialize)]
+struct DataResponse<T> {
+    data: T,
+}
+
+/// Result of creating a vault.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(from = "DataResponse<H160>")]
+pub struct CreateVaultResult {
+    /// Address of the new vault
+    pub vault_address: H160,
+}
+
+impl From<DataResponse<H160>> for CreateVaultResult {
+    fn from(response: DataResponse<H160>) -> Self {
+        Self {
+            vault_address: response.data,
+        }
+    }
+}
+
+/// Result of creating a sub-account.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(from = "DataResponse<H160>")]
+pub struct CreateSubAccountResult {
+    /// Address of the new sub-account
+    pub sub_account_user: H160,
+}
+
+impl From<DataResponse<H160>> for CreateSubAccountResult {
+    fn from(response: DataResponse<H160>) -> Self {
+        Self {
+            sub_account_user: response.data,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TwapRunning {
+    pub twap_id: u64,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub enum TwapStatus {
+    Success,
+    Running(TwapRunning),
+    Error(String),
+}
+
+#[derive(Deserialize)]
+struct TwapData {
+    status: TwapStatus,
+}
+
+/// Result of placing or cancelling a TWAP order.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(from = "DataResponse<TwapData>")]
+pub struct TwapResult {
+    pub status: TwapStatus,
+}
+
+impl From<DataResponse<TwapData>> for TwapResult {
+    fn from(response: DataResponse<TwapData>) -> Self {
+        Self {
+            status: response.data.status,
+        }
+    }
+}
+
+impl TwapResult {
+    /// Status of the TWAP order, with a rejection returned as [`Error::Exchange`].
+    pub fn into_status(self) -> Result<TwapStatus> {
+        match self.status {
+            TwapStatus::Error(e) => Err(Error::Exchange(e)),
+            status => Ok(status),
+        }
+    }
+
+    /// Id of the TWAP order started by a `twapOrder` action, with a rejection returned as
+    /// [`Error::Exchange`].
+    pub fn into_twap_id(self) -> Result<u64> {
+        match self.into_status()? {
+            TwapStatus::Running(running) => Ok(running.twap_id),
+            status => Err(Error::GenericParse(format!(
+                "expected a running TWAP, got {status:?}"
+            ))),
+        }
+    }
+}
+
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 #[serde(tag = "status", content = "response")]
-pub enum ExchangeResponseStatus {
-    Ok(ExchangeResponse),
+pub enum ExchangeResponseStatus<T = ExchangeResponse> {
+    Ok(T),
     Err(String),
 }
+
+impl<T> ExchangeResponseStatus<T> {
+    /// The response, or the error returned by the exchange as [`Error::Exchange`].
+    pub fn into_result(self) -> Result<T> {
+        match self {
+            ExchangeResponseStatus::Ok(response) => Ok(response),
+            ExchangeResponseStatus::Err(e) => Err(Error::Exchange(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typed_results_test() -> Result<()> {
+        let parse = |json: &str| -> Result<ExchangeResponseStatus<OrderResult>> {
+            serde_json::from_str(json).map_err(|e| Error::JsonParse(e.to_string()))
+        };
+
+        let result = parse(
+            r#"{"status":"ok","response":{"type":"order","data":{"statuses":[{"resting":{"oid":77738308}}]}}}"#,
+        )?;
+        assert!(matches!(
+            result.into_result()?.into_status()?,
+            ExchangeDataStatus::Resting(RestingOrder { oid: 77738308 })
+        ));
+
+        let result = parse(
+            r#"{"status":"ok","response":{"type":"order","data":{"statuses":[{"error":"Order must have minimum value of $10."}]}}}"#,
+        )?;
+        assert!(matches!(
+            result.into_result()?.into_status(),
+            Err(Error::Exchange(_))
+        ));
+
+        let result = parse(r#"{"status":"err","response":"User or API Wallet does not exist."}"#)?;
+        assert!(matches!(result.into_result(), Err(Error::Exchange(_))));
+
+        let result: ExchangeResponseStatus<TransferResult> =
+            serde_json::from_str(r#"{"status":"ok","response":{"type":"default"}}"#)
+                .map_err(|e| Error::JsonParse(e.to_string()))?;
+        assert!(result.into_result().is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn created_address_results_test() -> Result<()> {
+        let address = "0x1234567890abcdef1234567890abcdef12345678"
+            .parse::<H160>()
+            .map_err(|e| Error::GenericParse(e.to_string()))?;
+
+        let result: ExchangeResponseStatus<CreateVaultResult> = serde_json::from_str(
+            r#"{"status":"ok","response":{"type":"createVault","data":"0x1234567890abcdef1234567890abcdef12345678"}}"#,
+        )
+        .map_err(|e| Error::JsonParse(e.to_string()))?;
+        assert_eq!(result.into_result()?.vault_address, address);
+
+        let result: ExchangeResponseStatus<CreateSubAccountResult> = serde_json::from_str(
+            r#"{"status":"ok","response":{"type":"createSubAccount","data":"0x1234567890abcdef1234567890abcdef12345678"}}"#,
+        )
+        .map_err(|e| Error::JsonParse(e.to_string()))?;
+        assert_eq!(result.into_result()?.sub_account_user, address);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,319 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::{ClientOrder, ClientOrderRequest, Error, helpers::now_timestamp_ms, errors::Result};
+
+const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+/// Local pre-trade limits enforced by [`crate::ExchangeClient`] before signing orders and
+/// modifies, see [`crate::ExchangeClientBuilder::risk_limits`].
+///
+/// Every limit is optional. Reduce only orders are exempt from all limits, so positions
+/// can always be closed.
+#[derive(Debug, Default)]
+pub struct RiskLimits {
+    /// Maximum notional (`limit_px * sz`) of a single order, in USD
+    pub max_order_notional: Option<f64>,
+    /// Maximum absolute perp position per coin, in units of the coin, counting the
+    /// current position and the orders being sent
+    pub max_position: HashMap<String, f64>,
+    /// Maximum number of resting orders, counting the orders being sent
+    pub max_open_orders: Option<usize>,
+    /// Maximum relative deviation of limit prices from the mid, e.g. `0.05` for 5%.
+    /// Trigger orders are not checked.
+    pub price_band: Option<f64>,
+    /// Maximum loss of perp account value since the first check of the current UTC day,
+    /// in USD. Transfers in and out of the account count as profit and loss.
+    pub max_daily_loss: Option<f64>,
+    day_start: Mutex<Option<DayStart>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct DayStart {
+    day: u64,
+    account_value: f64,
+}
+
+/// Limit broken by an order, returned as [`Error::RiskLimit`].
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum RiskViolation {
+    #[error("order notional {notional} for {coin} is above the maximum of {max_notional}")]
+    OrderNotional {
+        coin: String,
+        notional: f64,
+        max_notional: f64,
+    },
+    #[error("position of {position} {coin} would be above the maximum of {max_position}")]
+    Position {
+        coin: String,
+        position: f64,
+        max_position: f64,
+    },
+    #[error("{open_orders} open orders would be above the maximum of {max_open_orders}")]
+    OpenOrders {
+        open_orders: usize,
+        max_open_orders: usize,
+    },
+    #[error("price {px} for {coin} is more than {price_band} away from the mid {mid}")]
+    PriceBand {
+        coin: String,
+        px: f64,
+        mid: f64,
+        price_band: f64,
+    },
+    #[error("daily loss of {loss} is above the maximum of {max_loss}")]
+    DailyLoss { loss: f64, max_loss: f64 },
+}
+
+/// Account state the limits are checked against.
+#[derive(Debug, Default)]
+pub(crate) struct RiskSnapshot {
+    /// Signed perp positions by coin
+    pub(crate) positions: HashMap<String, f64>,
+    /// Mid prices by coin
+    pub(crate) mids: HashMap<String, f64>,
+    pub(crate) open_orders: usize,
+    /// Perp account value
+    pub(crate) account_value: f64,
+}
+
+impl RiskLimits {
+    pub(crate) fn needs_positions(&self) -> bool {
+        !self.max_position.is_empty()
+    }
+
+    pub(crate) fn needs_mids(&self) -> bool {
+        self.price_band.is_some()
+    }
+
+    pub(crate) fn needs_open_orders(&self) -> bool {
+        self.max_open_orders.is_some()
+    }
+
+    pub(crate) fn needs_account_value(&self) -> bool {
+        self.max_daily_loss.is_some()
+    }
+
+    /// Checks `orders` against the limits. `new_orders` is false for modifies, which
+    /// replace resting orders instead of adding to them.
+    pub(crate) fn check(
+        &self,
+        orders: &[ClientOrderRequest],
+        new_orders: bool,
+        snapshot: &RiskSnapshot,
+    ) -> Result<()> {
+        self.check_at(orders, new_orders, snapshot, now_timestamp_ms())
+    }
+
+    fn check_at(
+        &self,
+        orders: &[ClientOrderRequest],
+        new_orders: bool,
+        snapshot: &RiskSnapshot,
+        now_ms: u64,
+    ) -> Result<()> {
+        let increasing: Vec<&ClientOrderRequest> =
+            orders.iter().filter(|order| !order.reduce_only).collect();
+        if increasing.is_empty() {
+            return Ok(());
+        }
+
+        for order in &increasing {
+            self.check_order(order, snapshot)?;
+        }
+
+        if let Some(max_loss) = self.max_daily_loss {
+            let loss = self.daily_loss(snapshot.account_value, now_ms);
+            if loss > max_loss {
+                return Err(Error::RiskLimit(RiskViolation::DailyLoss {
+                    loss,
+                    max_loss,
+                }));
+            }
+        }
+
+        if let (Some(max_open_orders), true) = (self.max_open_orders, new_orders) {
+            let open_orders = snapshot.open_orders + increasing.len();
+            if open_orders > max_open_orders {
+                return Err(Error::RiskLimit(RiskViolation::OpenOrders {
+                    open_orders,
+                    max_open_orders,
+                }));
+            }
+        }
+
+        let mut positions: HashMap<&str, f64> = HashMap::new();
+        for order in increasing {
+            let Some(&max_position) = self.max_position.get(&order.asset) else {
+                continue;
+            };
+            let position = positions.entry(&order.asset).or_insert_with(|| {
+                snapshot
+                    .positions
+                    .get(&order.asset)
+                    .copied()
+                    .unwrap_or_default()
+            });
+            *position += if order.is_buy { order.sz } else { -order.sz };
+            if position.abs() > max_position {
+                return Err(Error::RiskLimit(RiskViolation::Position {
+                    coin: order.asset.clone(),
+                    position: *position,
+                    max_position,
+                }));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_order(&self, order: &ClientOrderRequest, snapshot: &RiskSnapshot) -> Result<()> {
+        if let Some(max_notional) = self.max_order_notional {
+            let notional = order.limit_px * order.sz;
+            if notional > max_notional {
+                return Err(Error::RiskLimit(RiskViolation::OrderNotional {
+                    coin: order.asset.clone(),
+                    notional,
+                    max_notional,
+                }));
+            }
+        }
+
+        if let (Some(price_band), ClientOrder::Limit(_)) = (self.price_band, &order.order_type) {
+            let mid = *snapshot
+                .mids
+                .get(&order.asset)
+                .ok_or(Error::AssetNotFound)?;
+            if (order.limit_px - mid).abs() > mid * price_band {
+                return Err(Error::RiskLimit(RiskViolation::PriceBand {
+                    coin: order.asset.clone(),
+                    px: order.limit_px,
+                    mid,
+                    price_band,
+                }));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Loss since the start of the day, recording `account_value` as the start of the day
+    /// on the first call of each UTC day.
+    fn daily_loss(&self, account_value: f64, now_ms: u64) -> f64 {
+        let day = now_ms / DAY_MS;
+        let mut day_start = self.day_start.lock().unwrap_or_else(|e| e.into_inner());
+        let start = match *day_start {
+            Some(start) if start.day == day => start,
+            _ => *day_start.insert(DayStart { day, account_value }),
+        };
+
+        start.account_value - account_value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ClientLimit, LimitTif};
+
+    fn order(is_buy: bool, limit_px: f64, sz: f64) -> ClientOrderRequest {
+        ClientOrderRequest {
+            asset: "ETH".to_string(),
+            is_buy,
+            reduce_only: false,
+            limit_px,
+            sz,
+            cloid: None,
+            order_type: ClientOrder::Limit(ClientLimit { tif: LimitTif::Gtc }),
+        }
+    }
+
+    fn snapshot() -> RiskSnapshot {
+        RiskSnapshot {
+            positions: HashMap::from([("ETH".to_string(), 2.0)]),
+            mids: HashMap::from([("ETH".to_string(), 2000.0)]),
+            open_orders: 3,
+            account_value: 10000.0,
+        }
+    }
+
+    fn violation(result: Result<()>) -> RiskViolation {
+        match result {
+            Err(Error::RiskLimit(violation)) => violation,
+            result => panic!("expected a risk violation, got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn risk_limits_test() {
+        let snapshot = snapshot();
+        let limits = RiskLimits {
+            max_order_notional: Some(5000.0),
+            max_position: HashMap::from([("ETH".to_string(), 3.0)]),
+            max_open_orders: Some(4),
+            price_band: Some(0.05),
+            ..Default::default()
+        };
+
+        assert!(
+            limits
+                .check(&[order(true, 2000.0, 1.0)], true, &snapshot)
+                .is_ok()
+        );
+        assert!(matches!(
+            violation(limits.check(&[order(true, 2000.0, 3.0)], true, &snapshot)),
+            RiskViolation::OrderNotional { .. }
+        ));
+        assert!(matches!(
+            violation(limits.check(&[order(true, 2200.0, 1.0)], true, &snapshot)),
+            RiskViolation::PriceBand { .. }
+        ));
+        assert!(matches!(
+            violation(limits.check(
+                &[order(true, 2000.0, 0.6), order(true, 2000.0, 0.6)],
+                false,
+                &snapshot
+            )),
+            RiskViolation::Position { .. }
+        ));
+        assert!(matches!(
+            violation(limits.check(
+                &[order(false, 2000.0, 0.1), order(false, 2000.0, 0.1)],
+                true,
+                &snapshot
+            )),
+            RiskViolation::OpenOrders { .. }
+        ));
+
+        // Outside the price band and above the notional cap, but only closing the position
+        let mut close = order(false, 1800.0, 5.0);
+        close.reduce_only = true;
+        close.order_type = ClientOrder::Limit(ClientLimit { tif: LimitTif::Ioc });
+        assert!(limits.check(&[close], true, &snapshot).is_ok());
+    }
+
+    #[test]
+    fn daily_loss_test() {
+        let limits = RiskLimits {
+            max_daily_loss: Some(500.0),
+            ..Default::default()
+        };
+        let mut snapshot = snapshot();
+        let orders = [order(true, 2000.0, 1.0)];
+
+        assert!(limits.check_at(&orders, true, &snapshot, DAY_MS).is_ok());
+        snapshot.account_value = 9400.0;
+        assert_eq!(
+            violation(limits.check_at(&orders, true, &snapshot, DAY_MS + 1)),
+            RiskViolation::DailyLoss {
+                loss: 600.0,
+                max_loss: 500.0
+            }
+        );
+        assert!(
+            limits
+                .check_at(&orders, true, &snapshot, 2 * DAY_MS)
+                .is_ok()
+        );
+    }
+}
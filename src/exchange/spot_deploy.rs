@@ -0,0 +1,82 @@
+use ethers::types::H160;
+
+/// Steps of the spot deployment flow, sent as the `spotDeploy` action.
+///
+/// A token is deployed with [`SpotDeploy::RegisterToken2`], then
+/// [`SpotDeploy::UserGenesis`] and [`SpotDeploy::Genesis`], and listed with
+/// [`SpotDeploy::RegisterSpot`] and optionally [`SpotDeploy::RegisterHyperliquidity`].
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub enum SpotDeploy {
+    RegisterToken2(RegisterToken2),
+    UserGenesis(UserGenesis),
+    Genesis(Genesis),
+    RegisterSpot(RegisterSpot),
+    RegisterHyperliquidity(RegisterHyperliquidity),
+    SetDeployerTradingFeeShare(SetDeployerTradingFeeShare),
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenSpec {
+    pub name: String,
+    pub sz_decimals: u32,
+    pub wei_decimals: u32,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterToken2 {
+    pub spec: TokenSpec,
+    /// Maximum gas, in HYPE wei, the deployer is willing to pay in the deploy auction
+    pub max_gas: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub full_name: Option<String>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UserGenesis {
+    pub token: u32,
+    /// Initial balances as `(user, wei)` pairs
+    pub user_and_wei: Vec<(H160, String)>,
+    /// Balances given to the holders of an existing token as `(token, wei)` pairs
+    pub existing_token_and_wei: Vec<(u32, String)>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Genesis {
+    pub token: u32,
+    /// Maximum supply in wei, must match the sum of the user genesis balances
+    pub max_supply: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub no_hyperliquidity: bool,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterSpot {
+    /// Base and quote token indices
+    pub tokens: [u32; 2],
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterHyperliquidity {
+    /// Spot universe index of the pair
+    pub spot: u32,
+    pub start_px: String,
+    pub order_sz: String,
+    pub n_orders: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n_seeded_levels: Option<u32>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SetDeployerTradingFeeShare {
+    pub token: u32,
+    /// Share of the trading fees going to the deployer, e.g. `"100%"`
+    pub share: String,
+}
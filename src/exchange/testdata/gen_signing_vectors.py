@@ -0,0 +1,603 @@
+#!/usr/bin/env python3
+"""Generates signing_vectors.json.
+
+A dependency free port of `hyperliquid/utils/signing.py` from the Python SDK: msgpack
+action hashing, phantom agent signing of L1 actions and EIP-712 signing of user signed
+actions, with keccak-256, secp256k1 and RFC 6979 nonces implemented below.
+
+The vectors hard-coded in the Python SDK tests are kept with `"source": "python-sdk"` and
+must be reproduced exactly before anything is written. The other vectors are computed by
+this port, from the action dicts as the Python SDK builds them, with `"source":
+"python-port"`. The dicts keep the key order of the Python SDK, which the msgpack hash
+depends on.
+
+Usage: python3 gen_signing_vectors.py [--check]
+"""
+
+import hashlib
+import hmac
+import json
+import os
+import sys
+
+# Wallet of the Rust tests, see `signer()` in src/exchange/signer.rs
+PRIVATE_KEY = 0xE908F86DBB4D55AC876378565AAFEABC187F6690F046459397B17D9B9A19688E
+
+# keccak-256
+
+_RC = [
+    0x0000000000000001, 0x0000000000008082, 0x800000000000808A, 0x8000000080008000,
+    0x000000000000808B, 0x0000000080000001, 0x8000000080008081, 0x8000000000008009,
+    0x000000000000008A, 0x0000000000000088, 0x0000000080008009, 0x000000008000000A,
+    0x000000008000808B, 0x800000000000008B, 0x8000000000008089, 0x8000000000008003,
+    0x8000000000008002, 0x8000000000000080, 0x000000000000800A, 0x800000008000000A,
+    0x8000000080008081, 0x8000000000008080, 0x0000000080000001, 0x8000000080008008,
+]
+_ROT = [
+    [0, 36, 3, 41, 18], [1, 44, 10, 45, 2], [62, 6, 43, 15, 61],
+    [28, 55, 25, 21, 56], [27, 20, 39, 8, 14],
+]
+_MASK = (1 << 64) - 1
+
+
+def _rol(x, n):
+    return ((x << n) | (x >> (64 - n))) & _MASK if n else x
+
+
+def _keccak_f(a):
+    for rc in _RC:
+        c = [a[x][0] ^ a[x][1] ^ a[x][2] ^ a[x][3] ^ a[x][4] for x in range(5)]
+        d = [c[(x - 1) % 5] ^ _rol(c[(x + 1) % 5], 1) for x in range(5)]
+        a = [[a[x][y] ^ d[x] for y in range(5)] for x in range(5)]
+        b = [[0] * 5 for _ in range(5)]
+        for x in range(5):
+            for y in range(5):
+                b[y][(2 * x + 3 * y) % 5] = _rol(a[x][y], _ROT[x][y])
+        a = [
+            [b[x][y] ^ (~b[(x + 1) % 5][y] & b[(x + 2) % 5][y]) for y in range(5)]
+            for x in range(5)
+        ]
+        a[0][0] ^= rc
+    return a
+
+
+def keccak(data):
+    rate = 136
+    data = bytearray(data) + b"\x01"
+    data += b"\x00" * (-len(data) % rate)
+    data[-1] |= 0x80
+    a = [[0] * 5 for _ in range(5)]
+    for block in range(0, len(data), rate):
+        for i in range(rate // 8):
+            a[i % 5][i // 5] ^= int.from_bytes(data[block + 8 * i : block + 8 * i + 8], "little")
+        a = _keccak_f(a)
+    return b"".join(a[i % 5][i // 5].to_bytes(8, "little") for i in range(4))
+
+
+# msgpack, as `msgpack.packb` encodes the action dicts
+
+
+def packb(value):
+    if value is None:
+        return b"\xc0"
+    if value is True:
+        return b"\xc3"
+    if value is False:
+        return b"\xc2"
+    if isinstance(value, int):
+        if 0 <= value < 0x80:
+            return bytes([value])
+        if -32 <= value < 0:
+            return (value & 0xFF).to_bytes(1, "big")
+        if value >= 0:
+            for tag, size in ((0xCC, 1), (0xCD, 2), (0xCE, 4), (0xCF, 8)):
+                if value < 1 << (8 * size):
+                    return bytes([tag]) + value.to_bytes(size, "big")
+        for tag, size in ((0xD0, 1), (0xD1, 2), (0xD2, 4), (0xD3, 8)):
+            if value >= -(1 << (8 * size - 1)):
+                return bytes([tag]) + value.to_bytes(size, "big", signed=True)
+        raise ValueError(value)
+    if isinstance(value, str):
+        raw = value.encode()
+        if len(raw) < 32:
+            return bytes([0xA0 | len(raw)]) + raw
+        for tag, size in ((0xD9, 1), (0xDA, 2), (0xDB, 4)):
+            if len(raw) < 1 << (8 * size):
+                return bytes([tag]) + len(raw).to_bytes(size, "big") + raw
+    if isinstance(value, (list, tuple)):
+        head = bytes([0x90 | len(value)]) if len(value) < 16 else b"\xdc" + len(value).to_bytes(2, "big")
+        return head + b"".join(packb(item) for item in value)
+    if isinstance(value, dict):
+        head = bytes([0x80 | len(value)]) if len(value) < 16 else b"\xde" + len(value).to_bytes(2, "big")
+        return head + b"".join(packb(k) + packb(v) for k, v in value.items())
+    raise TypeError(value)
+
+
+# secp256k1 with RFC 6979 nonces, as eth_keys signs
+
+P = 2**256 - 2**32 - 977
+N = 0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141
+G = (
+    0x79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798,
+    0x483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8,
+)
+
+
+def _add(p, q):
+    if p is None:
+        return q
+    if q is None:
+        return p
+    if p[0] == q[0] and (p[1] + q[1]) % P == 0:
+        return None
+    if p == q:
+        m = 3 * p[0] * p[0] * pow(2 * p[1], -1, P)
+    else:
+        m = (q[1] - p[1]) * pow(q[0] - p[0], -1, P)
+    x = (m * m - p[0] - q[0]) % P
+    return (x, (m * (p[0] - x) - p[1]) % P)
+
+
+def _mul(k, point=G):
+    result = None
+    while k:
+        if k & 1:
+            result = _add(result, point)
+        point = _add(point, point)
+        k >>= 1
+    return result
+
+
+def sign_hash(msg_hash):
+    key = PRIVATE_KEY.to_bytes(32, "big")
+    mac = lambda k, m: hmac.new(k, m, hashlib.sha256).digest()
+    v, k = b"\x01" * 32, b"\x00" * 32
+    k = mac(k, v + b"\x00" + key + msg_hash)
+    v = mac(k, v)
+    k = mac(k, v + b"\x01" + key + msg_hash)
+    v = mac(k, v)
+    nonce = int.from_bytes(mac(k, v), "big")
+
+    r_point = _mul(nonce)
+    r = r_point[0] % N
+    s = pow(nonce, -1, N) * (int.from_bytes(msg_hash, "big") + r * PRIVATE_KEY) % N
+    v = 27 + (r_point[1] & 1)
+    if s * 2 >= N:
+        s, v = N - s, 27 + 28 - v
+    return r.to_bytes(32, "big").hex() + s.to_bytes(32, "big").hex() + bytes([v]).hex()
+
+
+# EIP-712
+
+
+def _encode_value(kind, value):
+    if kind == "string":
+        return keccak(value.encode())
+    if kind == "bytes32":
+        return value
+    if kind == "address":
+        return bytes(12) + bytes.fromhex(value[2:])
+    if kind == "bool":
+        return int(value).to_bytes(32, "big")
+    if kind.startswith("uint"):
+        return value.to_bytes(32, "big")
+    raise ValueError(kind)
+
+
+def _struct_hash(name, fields, message):
+    type_string = f"{name}({','.join(f'{kind} {field}' for field, kind in fields)})"
+    encoded = keccak(type_string.encode())
+    for field, kind in fields:
+        encoded += _encode_value(kind, message[field])
+    return keccak(encoded)
+
+
+def sign_typed_data(domain, primary_type, fields, message):
+    domain_fields = [
+        ("name", "string"),
+        ("version", "string"),
+        ("chainId", "uint256"),
+        ("verifyingContract", "address"),
+    ]
+    digest = keccak(
+        b"\x19\x01"
+        + _struct_hash("EIP712Domain", domain_fields, domain)
+        + _struct_hash(primary_type, fields, message)
+    )
+    return sign_hash(digest)
+
+
+# signing.py
+
+
+def action_hash(action, vault_address, nonce):
+    data = packb(action) + nonce.to_bytes(8, "big")
+    if vault_address is None:
+        data += b"\x00"
+    else:
+        data += b"\x01" + bytes.fromhex(vault_address[2:])
+    return keccak(data)
+
+
+def sign_l1_action(action, vault_address, nonce, is_mainnet):
+    connection_id = action_hash(action, vault_address, nonce)
+    domain = {
+        "name": "Exchange",
+        "version": "1",
+        "chainId": 1337,
+        "verifyingContract": "0x0000000000000000000000000000000000000000",
+    }
+    agent = {"source": "a" if is_mainnet else "b", "connectionId": connection_id}
+    fields = [("source", "string"), ("connectionId", "bytes32")]
+    return "0x" + connection_id.hex(), sign_typed_data(domain, "Agent", fields, agent)
+
+
+USER_SIGNED_TYPES = {
+    "usdSend": ("UsdSend", [("destination", "string"), ("amount", "string"), ("time", "uint64")]),
+    "withdraw3": ("Withdraw", [("destination", "string"), ("amount", "string"), ("time", "uint64")]),
+    "spotSend": (
+        "SpotSend",
+        [("destination", "string"), ("token", "string"), ("amount", "string"), ("time", "uint64")],
+    ),
+    "approveAgent": (
+        "ApproveAgent",
+        [("agentAddress", "address"), ("agentName", "string"), ("nonce", "uint64")],
+    ),
+    "approveBuilderFee": (
+        "ApproveBuilderFee",
+        [("maxFeeRate", "string"), ("builder", "address"), ("nonce", "uint64")],
+    ),
+    "cDeposit": ("CDeposit", [("wei", "uint64"), ("nonce", "uint64")]),
+    "cWithdraw": ("CWithdraw", [("wei", "uint64"), ("nonce", "uint64")]),
+    "tokenDelegate": (
+        "TokenDelegate",
+        [("validator", "address"), ("wei", "uint64"), ("isUndelegate", "bool"), ("nonce", "uint64")],
+    ),
+}
+
+
+def sign_user_signed_action(action):
+    name, fields = USER_SIGNED_TYPES[action["type"]]
+    domain = {
+        "name": "HyperliquidSignTransaction",
+        "version": "1",
+        "chainId": int(action["signatureChainId"], 16),
+        "verifyingContract": "0x0000000000000000000000000000000000000000",
+    }
+    # An unnamed agent is signed with an empty name, then the name is left out
+    message = {"agentName": "", **action}
+    fields = [("hyperliquidChain", "string")] + fields
+    return sign_typed_data(domain, f"HyperliquidTransaction:{name}", fields, message)
+
+
+# Vectors
+
+VAULT = "0x1719884eb866cb12b2287399b15f7db5e7d775ea"
+SUB_ACCOUNT = "0x1d9470d4b963f552e6f671a81619d395877bf409"
+DESTINATION = "0x0D1d9635D0640821d15e323ac8AdADfA9c111414"
+BUILDER = "0x8c967e73e7b15087c42a10d344cff4c96d877f1d"
+CLOID = "0x1e60610f0b3d420597c88c1fed2ad5ee"
+PURR = "PURR:0xc4bf3f870c0e9465323c0b6ed28096c2"
+L1_NONCE = 1583838
+USER_NONCE = 1690393044548
+
+
+def order_wire(a, b, p, s, r, t, c=None):
+    wire = {"a": a, "b": b, "p": p, "s": s, "r": r, "t": t}
+    if c is not None:
+        wire["c"] = c
+    return wire
+
+
+def limit(tif):
+    return {"limit": {"tif": tif}}
+
+
+def trigger(is_market, trigger_px, tpsl):
+    return {"trigger": {"isMarket": is_market, "triggerPx": trigger_px, "tpsl": tpsl}}
+
+
+def order_action(orders, grouping="na", builder=None):
+    action = {"type": "order", "orders": orders, "grouping": grouping}
+    if builder is not None:
+        action["builder"] = builder
+    return action
+
+
+def user_signed(kind, is_mainnet, chain_id="0x66eee", **fields):
+    action = {"type": kind, "signatureChainId": chain_id}
+    action["hyperliquidChain"] = "Mainnet" if is_mainnet else "Testnet"
+    action.update(fields)
+    return action
+
+
+ETH_IOC = order_wire(1, True, "2000.0", "3.5", False, limit("Ioc"))
+ETH_GTC = order_wire(1, True, "2000.0", "3.5", False, limit("Gtc"))
+
+
+def l1_vectors():
+    """(name, is_mainnet, nonce, vault_address, action) of the L1 actions."""
+    vectors = [
+        ("order_limit_vault", True, L1_NONCE, VAULT, order_action([ETH_GTC])),
+        (
+            "order_alo_reduce_only", True, L1_NONCE, None,
+            order_action([order_wire(1, True, "1234.5", "0.001", True, limit("Alo"))]),
+        ),
+        ("order_builder", True, L1_NONCE, None, order_action([ETH_IOC], builder={"b": BUILDER, "f": 10})),
+        (
+            "order_builder_vault", False, L1_NONCE, VAULT,
+            order_action([ETH_GTC], builder={"b": BUILDER, "f": 1}),
+        ),
+        (
+            "order_normal_tpsl", True, L1_NONCE, None,
+            order_action(
+                [
+                    ETH_GTC,
+                    order_wire(1, False, "2200.0", "3.5", True, trigger(False, "2200.0", "tp")),
+                    order_wire(1, False, "1800.0", "3.5", True, trigger(True, "1800.0", "sl")),
+                ],
+                "normalTpsl",
+            ),
+        ),
+        (
+            "order_position_tpsl", True, L1_NONCE, None,
+            order_action(
+                [order_wire(1, False, "2200.0", "0.0", True, trigger(True, "2190.0", "tp"))],
+                "positionTpsl",
+            ),
+        ),
+        (
+            "order_spot", True, L1_NONCE, None,
+            order_action([order_wire(10107, True, "10.123", "12.0", False, limit("Gtc"))]),
+        ),
+        (
+            "cancel_vault", True, L1_NONCE, VAULT,
+            {"type": "cancel", "cancels": [{"a": 1, "o": 82382}, {"a": 0, "o": 82383}]},
+        ),
+        (
+            "cancel_by_cloid", True, L1_NONCE, None,
+            {"type": "cancelByCloid", "cancels": [{"asset": 1, "cloid": CLOID}]},
+        ),
+        (
+            "cancel_by_cloid_vault", False, L1_NONCE, VAULT,
+            {"type": "cancelByCloid", "cancels": [{"asset": 1, "cloid": CLOID}]},
+        ),
+        (
+            "batch_modify_oid", True, L1_NONCE, None,
+            {"type": "batchModify", "modifies": [{"oid": 82382, "order": ETH_GTC}]},
+        ),
+        (
+            "batch_modify_cloid_vault", True, L1_NONCE, VAULT,
+            {
+                "type": "batchModify",
+                "modifies": [
+                    {
+                        "oid": CLOID,
+                        "order": order_wire(1, True, "2000.0", "3.5", False, limit("Gtc"), CLOID),
+                    }
+                ],
+            },
+        ),
+        (
+            "update_leverage", True, L1_NONCE, None,
+            {"type": "updateLeverage", "asset": 1, "isCross": True, "leverage": 10},
+        ),
+        (
+            "update_isolated_margin_vault", True, L1_NONCE, VAULT,
+            {"type": "updateIsolatedMargin", "asset": 1, "isBuy": True, "ntli": -1000000},
+        ),
+        (
+            "spot_user", True, L1_NONCE, None,
+            {"type": "spotUser", "classTransfer": {"usdc": 1000000, "toPerp": True}},
+        ),
+        (
+            "vault_transfer", True, L1_NONCE, None,
+            {"type": "vaultTransfer", "vaultAddress": VAULT, "isDeposit": True, "usd": 5000000},
+        ),
+        ("set_referrer", True, L1_NONCE, None, {"type": "setReferrer", "code": "TESTNET"}),
+        (
+            "twap_order", True, L1_NONCE, None,
+            {"type": "twapOrder", "twap": {"a": 1, "b": True, "s": "3.5", "r": False, "m": 30, "t": False}},
+        ),
+        (
+            "twap_order_vault", False, L1_NONCE, VAULT,
+            {"type": "twapOrder", "twap": {"a": 0, "b": False, "s": "0.1", "r": True, "m": 5, "t": True}},
+        ),
+        ("twap_cancel", True, L1_NONCE, None, {"type": "twapCancel", "a": 1, "t": 42}),
+        # Inputs of the Python SDK schedule cancel and sub-account tests
+        ("schedule_cancel_mainnet", True, 0, None, {"type": "scheduleCancel", "time": 123456789}),
+        ("schedule_cancel_testnet", False, 0, None, {"type": "scheduleCancel", "time": 123456789}),
+        ("schedule_cancel_disarm_mainnet", True, 0, None, {"type": "scheduleCancel"}),
+        ("schedule_cancel_disarm_testnet", False, 0, None, {"type": "scheduleCancel"}),
+        ("schedule_cancel_vault", True, L1_NONCE, VAULT, {"type": "scheduleCancel", "time": 1583848000}),
+        ("create_sub_account_mainnet", True, 0, None, {"type": "createSubAccount", "name": "example"}),
+        ("create_sub_account_testnet", False, 0, None, {"type": "createSubAccount", "name": "example"}),
+        (
+            "sub_account_transfer_mainnet", True, 0, None,
+            {"type": "subAccountTransfer", "subAccountUser": SUB_ACCOUNT, "isDeposit": True, "usd": 10},
+        ),
+        (
+            "sub_account_transfer_testnet", False, 0, None,
+            {"type": "subAccountTransfer", "subAccountUser": SUB_ACCOUNT, "isDeposit": True, "usd": 10},
+        ),
+        (
+            "sub_account_spot_transfer", True, L1_NONCE, None,
+            {
+                "type": "subAccountSpotTransfer",
+                "subAccountUser": SUB_ACCOUNT,
+                "isDeposit": False,
+                "token": PURR,
+                "amount": "1.5",
+            },
+        ),
+        (
+            "spot_deploy_register_token2", True, L1_NONCE, None,
+            {
+                "type": "spotDeploy",
+                "registerToken2": {
+                    "spec": {"name": "TEST", "szDecimals": 2, "weiDecimals": 8},
+                    "maxGas": 1000000000000,
+                    "fullName": "Test token",
+                },
+            },
+        ),
+        (
+            "spot_deploy_user_genesis", True, L1_NONCE, None,
+            {
+                "type": "spotDeploy",
+                "userGenesis": {
+                    "token": 1234,
+                    "userAndWei": [["0x0d1d9635d0640821d15e323ac8adadfa9c111414", "100000000"]],
+                    "existingTokenAndWei": [[0, "100"]],
+                },
+            },
+        ),
+        (
+            "spot_deploy_genesis", True, L1_NONCE, None,
+            {
+                "type": "spotDeploy",
+                "genesis": {"token": 1234, "maxSupply": "100000000", "noHyperliquidity": True},
+            },
+        ),
+        (
+            "spot_deploy_register_spot", True, L1_NONCE, None,
+            {"type": "spotDeploy", "registerSpot": {"tokens": [1234, 0]}},
+        ),
+        (
+            "spot_deploy_register_hyperliquidity", True, L1_NONCE, None,
+            {
+                "type": "spotDeploy",
+                "registerHyperliquidity": {
+                    "spot": 200,
+                    "startPx": "1.0",
+                    "orderSz": "100.0",
+                    "nOrders": 10,
+                    "nSeededLevels": 2,
+                },
+            },
+        ),
+        (
+            "spot_deploy_set_trading_fee_share", True, L1_NONCE, None,
+            {"type": "spotDeploy", "setDeployerTradingFeeShare": {"token": 1234, "share": "100%"}},
+        ),
+        (
+            "create_vault", True, L1_NONCE, None,
+            {
+                "type": "createVault",
+                "name": "Test vault",
+                "description": "Golden vector",
+                "initialUsd": 100000000,
+                "nonce": L1_NONCE,
+            },
+        ),
+        (
+            "vault_modify", True, L1_NONCE, None,
+            {
+                "type": "vaultModify",
+                "vaultAddress": VAULT,
+                "allowDeposits": True,
+                "alwaysCloseOnWithdraw": None,
+            },
+        ),
+        (
+            "vault_distribute", True, L1_NONCE, None,
+            {"type": "vaultDistribute", "vaultAddress": VAULT, "usd": 1000000},
+        ),
+    ]
+    return vectors
+
+
+def user_signed_vectors():
+    """(name, is_mainnet, nonce, vault_address, action) of the user signed actions."""
+    n = USER_NONCE
+    return [
+        ("usd_send_mainnet", True, n, None, user_signed("usdSend", True, destination=DESTINATION, amount="1", time=n)),
+        # The vault address is not part of a user signed action
+        (
+            "usd_send_vault_ignored", True, n, VAULT,
+            user_signed("usdSend", True, destination=DESTINATION, amount="1", time=n),
+        ),
+        (
+            "usd_send_arbitrum_chain_id", True, n, None,
+            user_signed("usdSend", True, "0xa4b1", destination=DESTINATION, amount="1", time=n),
+        ),
+        ("withdraw3_mainnet", True, n, None, user_signed("withdraw3", True, destination=DESTINATION, amount="1", time=n)),
+        (
+            "spot_send", False, n, None,
+            user_signed("spotSend", False, destination=DESTINATION, token=PURR, amount="0.1", time=n),
+        ),
+        (
+            "approve_agent", True, n, None,
+            user_signed("approveAgent", True, agentAddress=BUILDER, agentName="bot", nonce=n),
+        ),
+        ("approve_agent_unnamed", False, n, None, user_signed("approveAgent", False, agentAddress=BUILDER, nonce=n)),
+        (
+            "approve_builder_fee_mainnet", True, n, None,
+            user_signed("approveBuilderFee", True, maxFeeRate="0.001%", builder=BUILDER, nonce=n),
+        ),
+        (
+            "approve_builder_fee_testnet", False, n, None,
+            user_signed("approveBuilderFee", False, maxFeeRate="0.1%", builder=BUILDER, nonce=n),
+        ),
+        ("c_deposit", True, n, None, user_signed("cDeposit", True, wei=100000000, nonce=n)),
+        ("c_withdraw", False, n, None, user_signed("cWithdraw", False, wei=100000000, nonce=n)),
+        (
+            "token_delegate", True, n, None,
+            user_signed("tokenDelegate", True, validator=VAULT, wei=100000000, isUndelegate=False, nonce=n),
+        ),
+        (
+            "token_undelegate", False, n, None,
+            user_signed("tokenDelegate", False, validator=VAULT, wei=1, isUndelegate=True, nonce=n),
+        ),
+    ]
+
+
+def sign_vector(is_mainnet, nonce, vault_address, action):
+    if action["type"] in USER_SIGNED_TYPES:
+        return None, sign_user_signed_action(action)
+    return sign_l1_action(action, vault_address, nonce, is_mainnet)
+
+
+def vector(name, source, is_mainnet, nonce, vault_address, action):
+    connection_id, signature = sign_vector(is_mainnet, nonce, vault_address, action)
+    result = {
+        "name": name,
+        "source": source,
+        "isMainnet": is_mainnet,
+        "nonce": nonce,
+        "vaultAddress": vault_address,
+        "action": action,
+    }
+    if connection_id is not None:
+        result["connectionId"] = connection_id
+    result["signature"] = signature
+    return result
+
+
+def main():
+    path = os.path.join(os.path.dirname(os.path.abspath(__file__)), "signing_vectors.json")
+    with open(path) as f:
+        existing = json.load(f)
+
+    python_sdk = [v for v in existing if v["source"] == "python-sdk"]
+    for v in python_sdk:
+        connection_id, signature = sign_vector(v["isMainnet"], v["nonce"], v["vaultAddress"], v["action"])
+        assert signature == v["signature"], f"{v['name']}: port does not match the Python SDK"
+        assert connection_id == v.get("connectionId"), f"{v['name']}: connection id mismatch"
+
+    ported = [
+        vector(name, "python-port", is_mainnet, nonce, vault_address, action)
+        for name, is_mainnet, nonce, vault_address, action in l1_vectors() + user_signed_vectors()
+    ]
+    output = json.dumps(python_sdk + ported, indent=2) + "\n"
+
+    if "--check" in sys.argv:
+        with open(path) as f:
+            assert f.read() == output, "signing_vectors.json is out of date"
+        return
+    with open(path, "w") as f:
+        f.write(output)
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,163 @@
+use crate::{
+    asset_registry::AssetRegistry, errors::Result, exchange::actions::TwapRequest,
+    helpers::float_to_string_for_hashing,
+};
+
+#[derive(Debug)]
+pub struct ClientTwapRequest {
+    pub asset: String,
+    pub is_buy: bool,
+    pub sz: f64,
+    pub reduce_only: bool,
+    /// Duration of the TWAP in minutes
+    pub minutes: u32,
+    /// Randomize the size and timing of the slices
+    pub randomize: bool,
+}
+
+impl ClientTwapRequest {
+    pub(crate) fn to_twap_request(&self, asset_registry: &AssetRegistry) -> Result<TwapRequest> {
+        Ok(TwapRequest {
+            asset: asset_registry.asset(&self.asset)?.to_wire(),
+            is_buy: self.is_buy,
+            sz: float_to_string_for_hashing(self.sz),
+            reduce_only: self.reduce_only,
+            minutes: self.minutes,
+            randomize: self.randomize,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Error, TwapHistory, TwapResult, TwapSliceFill, TwapStatus,
+        exchange::{
+            actions::{TwapCancel, TwapOrder},
+            api::Actions,
+        },
+    };
+
+    fn asset_registry() -> Result<AssetRegistry> {
+        Ok(AssetRegistry::new(
+            serde_json::from_str(
+                r#"{"universe":[{"name":"BTC","szDecimals":5,"maxLeverage":40},
+                    {"name":"ETH","szDecimals":4,"maxLeverage":25}]}"#,
+            )
+            .map_err(|e| Error::JsonParse(e.to_string()))?,
+            serde_json::from_str(r#"{"universe":[],"tokens":[]}"#)
+                .map_err(|e| Error::JsonParse(e.to_string()))?,
+        ))
+    }
+
+    #[test]
+    fn twap_wire_format_test() -> Result<()> {
+        let twap = ClientTwapRequest {
+            asset: "ETH".to_string(),
+            is_buy: true,
+            sz: 3.5,
+            reduce_only: false,
+            minutes: 30,
+            randomize: false,
+        };
+        let action = Actions::TwapOrder(TwapOrder {
+            twap: twap.to_twap_request(&asset_registry()?)?,
+        });
+        assert_eq!(
+            serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?,
+            serde_json::json!({
+                "type": "twapOrder",
+                "twap": {"a": 1, "b": true, "s": "3.5", "r": false, "m": 30, "t": false},
+            })
+        );
+        assert_eq!(
+            format!("{:?}", action.hash(1583838, None)?),
+            "0x5d621be752c363a7b12bfae4b41ec49884ddb6374d9acf3a2b05409d7c12d670"
+        );
+
+        let action = Actions::TwapCancel(TwapCancel {
+            asset: 1,
+            twap_id: 42,
+        });
+        assert_eq!(
+            serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?,
+            serde_json::json!({"type": "twapCancel", "a": 1, "t": 42})
+        );
+        assert_eq!(
+            format!("{:?}", action.hash(1583838, None)?),
+            "0xbfdffd0f93ffe5649b96a234b209c59536cdffd3d23c461db53a289bca5bb2d1"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn twap_result_test() -> Result<()> {
+        let parse = |json: &str| -> Result<TwapResult> {
+            serde_json::from_str::<crate::ExchangeResponseStatus<TwapResult>>(json)
+                .map_err(|e| Error::JsonParse(e.to_string()))?
+                .into_result()
+        };
+
+        let result = parse(
+            r#"{"status":"ok","response":{"type":"twapOrder","data":{"status":{"running":{"twapId":77738308}}}}}"#,
+        )?;
+        assert_eq!(result.into_twap_id()?, 77738308);
+
+        let result = parse(
+            r#"{"status":"ok","response":{"type":"twapOrder","data":{"status":{"error":"Invalid TWAP duration: 1 min(s)"}}}}"#,
+        )?;
+        assert!(matches!(
+            result.into_twap_id(),
+            Err(Error::Exchange(e)) if e == "Invalid TWAP duration: 1 min(s)"
+        ));
+
+        let result = parse(
+            r#"{"status":"ok","response":{"type":"twapCancel","data":{"status":"success"}}}"#,
+        )?;
+        assert!(matches!(result.into_status()?, TwapStatus::Success));
+
+        Ok(())
+    }
+
+    #[test]
+    fn twap_info_deserialization_test() -> Result<()> {
+        let history: Vec<TwapHistory> = serde_json::from_str(
+            r#"[{"time":1700000000,"state":{"coin":"ETH","user":"0x1719884eb866cb12b2287399b15f7db5e7d775ea",
+                "side":"B","sz":"3.5","executedSz":"0.7","executedNtl":"1400.0","minutes":30,
+                "reduceOnly":false,"randomize":false,"timestamp":1700000000000},
+                "status":{"status":"activated"},"twapId":42},
+                {"time":1600000000,"state":{"coin":"BTC","user":"0x1719884eb866cb12b2287399b15f7db5e7d775ea",
+                "side":"A","sz":"0.1","executedSz":"0.0","executedNtl":"0.0","minutes":5,
+                "reduceOnly":true,"randomize":true,"timestamp":1600000000000},
+                "status":{"status":"error","description":"Insufficient margin"}}]"#,
+        )
+        .map_err(|e| Error::JsonParse(e.to_string()))?;
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].twap_id, Some(42));
+        assert_eq!(history[0].state.executed_sz, "0.7");
+        assert_eq!(history[0].status.status, "activated");
+        assert_eq!(history[1].twap_id, None);
+        assert_eq!(
+            history[1].status.description.as_deref(),
+            Some("Insufficient margin")
+        );
+
+        let fills: Vec<TwapSliceFill> = serde_json::from_str(
+            r#"[{"fill":{"coin":"ETH","side":"B","px":"2000.5","sz":"0.1","time":1700000060000,
+                "hash":"0x0000000000000000000000000000000000000000000000000000000000000000",
+                "startPosition":"0.0","dir":"Open Long","closedPnl":"0.0","oid":77738308,
+                "cloid":null,"crossed":true,"fee":"0.05","feeToken":"USDC","tid":1},"twapId":42}]"#,
+        )
+        .map_err(|e| Error::JsonParse(e.to_string()))?;
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].twap_id, 42);
+        assert_eq!(
+            (fills[0].fill.px.as_str(), fills[0].fill.oid),
+            ("2000.5", 77738308)
+        );
+
+        Ok(())
+    }
+}
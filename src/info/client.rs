@@ -1,13 +1,15 @@
 use crate::{
-    BaseUrl, Error, Message, OrderStatusResponse, ReferralResponse, UserFeesResponse,
-    UserFundingResponse, UserTokenBalanceResponse,
+    ActiveAssetDataData, BaseUrl, Error, Message, OrderStatusResponse, PerpDex, ReferralResponse, TwapHistory,
+    TwapSliceFill, UserFeesResponse, UserFundingResponse, UserTokenBalanceResponse,
     info::{
-        CandlesSnapshotResponse, FundingHistoryResponse, L2SnapshotResponse, OpenOrdersResponse,
-        OrderInfo, RecentTradesResponse, UserFillsResponse, UserStateResponse,
+        CandlesSnapshotResponse, DelegationResponse, DelegatorRewardResponse,
+        DelegatorSummaryResponse, ExtraAgentResponse, FundingHistoryResponse, L2SnapshotResponse,
+        OpenOrdersResponse, OrderInfo, RecentTradesResponse, SubAccountResponse, UserFillsResponse,
+        UserStateResponse, UserVaultEquity, VaultDetailsResponse, VaultFollower,
         builder::InfoClientBuilder,
         request::{CandleSnapshotRequest, InfoRequest},
     },
-    meta::{Meta, SpotMeta, SpotMetaAndAssetCtxs},
+    meta::{Meta, MetaAndAssetCtxs, SpotMeta, SpotMetaAndAssetCtxs},
     req::{Endpoint, HttpClient},
     ws::{Subscription, WsManager},
     errors::Result,
@@ -132,6 +134,12 @@ impl InfoClient {
         self.send_info_request(input).await
     }
 
+    /// Perp metadata with funding, open interest and prices of every asset.
+    pub async fn meta_and_asset_ctxs(&self) -> Result<MetaAndAssetCtxs> {
+        let input = InfoRequest::MetaAndAssetCtxs;
+        self.send_info_request(input).await
+    }
+
     pub async fn spot_meta_and_asset_contexts(&self) -> Result<Vec<SpotMetaAndAssetCtxs>> {
         let input = InfoRequest::SpotMetaAndAssetCtxs;
         self.send_info_request(input).await
@@ -213,6 +221,134 @@ impl InfoClient {
         self.send_info_request(input).await
     }
 
+    pub async fn user_twap_slice_fills(&self, address: H160) -> Result<Vec<TwapSliceFill>> {
+        let input = InfoRequest::UserTwapSliceFills { user: address };
+        self.send_info_request(input).await
+    }
+
+    pub async fn twap_history(&self, address: H160) -> Result<Vec<TwapHistory>> {
+        let input = InfoRequest::TwapHistory { user: address };
+        self.send_info_request(input).await
+    }
+
+    /// Sub-accounts of `address`, empty if it has none.
+    pub async fn sub_accounts(&self, address: H160) -> Result<Vec<SubAccountResponse>> {
+        let input = InfoRequest::SubAccounts { user: address };
+        let sub_accounts: Option<Vec<SubAccountResponse>> = self.send_info_request(input).await?;
+        Ok(sub_accounts.unwrap_or_default())
+    }
+
+    /// Details of a vault. If `user` is given, its position in the vault is returned
+    /// in [`VaultDetailsResponse::follower_state`].
+    pub async fn vault_details(
+        &self,
+        vault_address: H160,
+        user: Option<H160>,
+    ) -> Result<VaultDetailsResponse> {
+        let input = InfoRequest::VaultDetails {
+            vault_address,
+            user,
+        };
+        self.send_info_request(input).await
+    }
+
+    pub async fn vault_followers(&self, vault_address: H160) -> Result<Vec<VaultFollower>> {
+        Ok(self.vault_details(vault_address, None).await?.followers)
+    }
+
+    pub async fn user_vault_equities(&self, address: H160) -> Result<Vec<UserVaultEquity>> {
+        let input = InfoRequest::UserVaultEquities { user: address };
+        self.send_info_request(input).await
+    }
+
+    pub async fn delegator_summary(&self, address: H160) -> Result<DelegatorSummaryResponse> {
+        let input = InfoRequest::DelegatorSummary { user: address };
+        self.send_info_request(input).await
+    }
+
+    pub async fn delegations(&self, address: H160) -> Result<Vec<DelegationResponse>> {
+        let input = InfoRequest::Delegations { user: address };
+        self.send_info_request(input).await
+    }
+
+    pub async fn delegator_rewards(&self, address: H160) -> Result<Vec<DelegatorRewardResponse>> {
+        let input = InfoRequest::DelegatorRewards { user: address };
+        self.send_info_request(input).await
+    }
+
+    /// Named agents approved by `address`.
+    pub async fn extra_agents(&self, address: H160) -> Result<Vec<ExtraAgentResponse>> {
+        let input = InfoRequest::ExtraAgents { user: address };
+        self.send_info_request(input).await
+    }
+
+    /// Maximum builder fee `address` approved for `builder`, in tenths of a basis point.
+    pub async fn max_builder_fee(&self, address: H160, builder: H160) -> Result<u64> {
+        let input = InfoRequest::MaxBuilderFee {
+            user: address,
+            builder,
+        };
+        self.send_info_request(input).await
+    }
+
+    /// Leverage, maximum trade sizes and available margin of `address` for `coin`.
+    pub async fn active_asset_data(
+        &self,
+        address: H160,
+        coin: &str,
+    ) -> Result<ActiveAssetDataData> {
+        let input = InfoRequest::ActiveAssetData {
+            user: address,
+            coin: coin.to_string(),
+        };
+        self.send_info_request(input).await
+    }
+
+    /// Perp dexes, indexed by their dex id. The default dex comes first as `None`.
+    pub async fn perp_dexs(&self) -> Result<Vec<Option<PerpDex>>> {
+        let input = InfoRequest::PerpDexs;
+        self.send_info_request(input).await
+    }
+
+    /// Perp universe of a builder-deployed perp dex.
+    pub async fn perp_dex_meta(&self, dex: &str) -> Result<Meta> {
+        let input = InfoRequest::PerpDexMeta {
+            dex: dex.to_string(),
+        };
+        self.send_info_request(input).await
+    }
+
+    pub async fn perp_dex_all_mids(
+        &self,
+        dex: &str,
+    ) -> Result<std::collections::HashMap<String, String>> {
+        let input = InfoRequest::PerpDexAllMids {
+            dex: dex.to_string(),
+        };
+        self.send_info_request(input).await
+    }
+
+    /// Perp positions and margin of `address` on a builder-deployed perp dex.
+    pub async fn perp_dex_user_state(&self, address: H160, dex: &str) -> Result<UserStateResponse> {
+        let input = InfoRequest::PerpDexUserState {
+            user: address,
+            dex: dex.to_string(),
+        };
+        self.send_info_request(input).await
+    }
+
+    pub async fn perp_dex_open_orders(
+        &self,
+        address: H160,
+        dex: &str,
+    ) -> Result<Vec<OpenOrdersResponse>> {
+        let input = InfoRequest::PerpDexOpenOrders {
+            user: address,
+            dex: dex.to_string(),
+        };
+        self.send_info_request(input).await
+    }
+
     /// Gracefully shuts down the WebSocket connection.
     ///
     /// This method is used to gracefully shut down the WebSocket connection.
@@ -84,4 +84,62 @@ pub enum InfoRequest<'a> {
     HistoricalOrders {
         user: ethers::types::H160,
     },
+    UserTwapSliceFills {
+        user: ethers::types::H160,
+    },
+    TwapHistory {
+        user: ethers::types::H160,
+    },
+    SubAccounts {
+        user: ethers::types::H160,
+    },
+    #[serde(rename_all = "camelCase")]
+    VaultDetails {
+        vault_address: ethers::types::H160,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        user: Option<ethers::types::H160>,
+    },
+    UserVaultEquities {
+        user: ethers::types::H160,
+    },
+    DelegatorSummary {
+        user: ethers::types::H160,
+    },
+    Delegations {
+        user: ethers::types::H160,
+    },
+    DelegatorRewards {
+        user: ethers::types::H160,
+    },
+    ExtraAgents {
+        user: ethers::types::H160,
+    },
+    MaxBuilderFee {
+        user: ethers::types::H160,
+        builder: ethers::types::H160,
+    },
+    PerpDexs,
+    ActiveAssetData {
+        user: ethers::types::H160,
+        coin: String,
+    },
+    MetaAndAssetCtxs,
+    #[serde(rename = "meta")]
+    PerpDexMeta {
+        dex: String,
+    },
+    #[serde(rename = "allMids")]
+    PerpDexAllMids {
+        dex: String,
+    },
+    #[serde(rename = "clearinghouseState")]
+    PerpDexUserState {
+        user: ethers::types::H160,
+        dex: String,
+    },
+    #[serde(rename = "openOrders")]
+    PerpDexOpenOrders {
+        user: ethers::types::H160,
+        dex: String,
+    },
 }
@@ -1,7 +1,9 @@
 use crate::{
     DailyUserVlm, Delta, FeeSchedule, OrderInfo, Referrer, ReferrerState, UserTokenBalance,
+    VaultFollower, VaultPortfolio, VaultRelationship,
     info::{AssetPosition, Level, MarginSummary},
 };
+use ethers::types::H160;
 use serde::Deserialize;
 
 #[derive(Deserialize, Debug)]
@@ -18,6 +20,16 @@ pub struct UserTokenBalanceResponse {
     pub balances: Vec<UserTokenBalance>,
 }
 
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SubAccountResponse {
+    pub name: String,
+    pub sub_account_user: H160,
+    pub master: H160,
+    pub clearinghouse_state: UserStateResponse,
+    pub spot_state: UserTokenBalanceResponse,
+}
+
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct UserFeesResponse {
@@ -133,3 +145,155 @@ pub struct ReferralResponse {
     pub claimed_rewards: String,
     pub referrer_state: ReferrerState,
 }
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultDetailsResponse {
+    pub name: String,
+    pub vault_address: H160,
+    pub leader: H160,
+    pub description: String,
+    /// Portfolio history per period (`day`, `week`, `month`, `allTime`, ...)
+    pub portfolio: Vec<(String, VaultPortfolio)>,
+    pub apr: f64,
+    /// State of the queried user in the vault, if a user was given and follows it
+    pub follower_state: Option<VaultFollower>,
+    pub leader_fraction: f64,
+    pub leader_commission: f64,
+    pub followers: Vec<VaultFollower>,
+    pub max_distributable: f64,
+    pub max_withdrawable: f64,
+    pub is_closed: bool,
+    pub relationship: VaultRelationship,
+    pub allow_deposits: bool,
+    pub always_close_on_withdraw: bool,
+}
+
+impl VaultDetailsResponse {
+    /// `(timestamp, pnl)` history of the vault over `period`, e.g. `day` or `allTime`.
+    pub fn pnl_history(&self, period: &str) -> Option<&[(u64, String)]> {
+        self.portfolio
+            .iter()
+            .find(|(name, _)| name == period)
+            .map(|(_, portfolio)| portfolio.pnl_history.as_slice())
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UserVaultEquity {
+    pub vault_address: H160,
+    pub equity: String,
+    pub locked_until_timestamp: Option<u64>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DelegatorSummaryResponse {
+    pub delegated: String,
+    pub undelegated: String,
+    pub total_pending_withdrawal: String,
+    pub n_pending_withdrawals: u64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DelegationResponse {
+    pub validator: H160,
+    pub amount: String,
+    pub locked_until_timestamp: u64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DelegatorRewardResponse {
+    pub time: u64,
+    /// Either `delegation` or `commission`
+    pub source: String,
+    pub total_amount: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtraAgentResponse {
+    pub name: String,
+    pub address: H160,
+    pub valid_until: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Error, errors::Result};
+
+    #[test]
+    fn sub_accounts_deserialization_test() -> Result<()> {
+        let sub_accounts: Option<Vec<SubAccountResponse>> = serde_json::from_str(
+            r#"[{"name":"example","subAccountUser":"0x1d9470d4b963f552e6f671a81619d395877bf409",
+                "master":"0x1719884eb866cb12b2287399b15f7db5e7d775ea",
+                "clearinghouseState":{"assetPositions":[],
+                    "crossMarginSummary":{"accountValue":"10.0","totalMarginUsed":"0.0","totalNtlPos":"0.0","totalRawUsd":"10.0"},
+                    "marginSummary":{"accountValue":"10.0","totalMarginUsed":"0.0","totalNtlPos":"0.0","totalRawUsd":"10.0"},
+                    "withdrawable":"10.0"},
+                "spotState":{"balances":[{"coin":"USDC","hold":"0.0","total":"1.5","entryNtl":"0.0"}]}}]"#,
+        )
+        .map_err(|e| Error::JsonParse(e.to_string()))?;
+        let sub_accounts = sub_accounts.unwrap_or_default();
+        assert_eq!(sub_accounts.len(), 1);
+        assert_eq!(sub_accounts[0].name, "example");
+        assert_eq!(
+            format!("{:?}", sub_accounts[0].sub_account_user),
+            "0x1d9470d4b963f552e6f671a81619d395877bf409"
+        );
+        assert_eq!(sub_accounts[0].clearinghouse_state.withdrawable, "10.0");
+        assert_eq!(sub_accounts[0].spot_state.balances[0].total, "1.5");
+
+        // users without sub-accounts get null
+        let sub_accounts: Option<Vec<SubAccountResponse>> =
+            serde_json::from_str("null").map_err(|e| Error::JsonParse(e.to_string()))?;
+        assert!(sub_accounts.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn vault_details_deserialization_test() -> Result<()> {
+        let details: VaultDetailsResponse = serde_json::from_str(
+            r#"{"name":"Test vault","vaultAddress":"0x1719884eb866cb12b2287399b15f7db5e7d775ea",
+                "leader":"0x1d9470d4b963f552e6f671a81619d395877bf409","description":"Golden vector",
+                "portfolio":[["day",{"accountValueHistory":[[1700000000000,"100.0"]],
+                    "pnlHistory":[[1700000000000,"0.0"],[1700003600000,"1.5"]],"vlm":"0.0"}],
+                    ["allTime",{"accountValueHistory":[],"pnlHistory":[],"vlm":"10.0"}]],
+                "apr":0.12,
+                "followerState":null,
+                "leaderFraction":0.5,"leaderCommission":0.1,
+                "followers":[{"user":"Leader","vaultEquity":"100.0","pnl":"1.5","allTimePnl":"1.5",
+                    "daysFollowing":3,"vaultEntryTime":1700000000000,"lockupUntil":1700086400000}],
+                "maxDistributable":50.0,"maxWithdrawable":40.0,"isClosed":false,
+                "relationship":{"type":"parent","data":{"childAddresses":["0x1d9470d4b963f552e6f671a81619d395877bf409"]}},
+                "allowDeposits":true,"alwaysCloseOnWithdraw":false}"#,
+        )
+        .map_err(|e| Error::JsonParse(e.to_string()))?;
+        assert_eq!(details.name, "Test vault");
+        assert!(details.follower_state.is_none());
+        assert_eq!(details.followers[0].user, "Leader");
+        assert_eq!(
+            details.pnl_history("day"),
+            Some(
+                &[
+                    (1700000000000, "0.0".to_string()),
+                    (1700003600000, "1.5".to_string())
+                ][..]
+            )
+        );
+        assert_eq!(details.pnl_history("week"), None);
+        assert_eq!(details.relationship.relationship_type, "parent");
+        assert_eq!(
+            details.relationship.data.map(|data| data.child_addresses),
+            Some(vec![details.leader])
+        );
+        assert!(details.allow_deposits && !details.is_closed);
+
+        Ok(())
+    }
+}
@@ -170,6 +170,55 @@ pub struct ReferrerData {
     pub required: String,
 }
 
+#[derive(serde::Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PerpDex {
+    pub name: String,
+    pub full_name: String,
+    pub deployer: ethers::types::H160,
+    pub oracle_updater: Option<ethers::types::H160>,
+}
+
+#[derive(serde::Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultPortfolio {
+    /// `(timestamp, account value)` pairs
+    pub account_value_history: Vec<(u64, String)>,
+    /// `(timestamp, pnl)` pairs
+    pub pnl_history: Vec<(u64, String)>,
+    pub vlm: String,
+}
+
+#[derive(serde::Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultFollower {
+    /// Follower address, or `Leader` for the vault leader
+    pub user: String,
+    pub vault_equity: String,
+    pub pnl: String,
+    pub all_time_pnl: String,
+    pub days_following: u64,
+    pub vault_entry_time: u64,
+    pub lockup_until: u64,
+}
+
+#[derive(serde::Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultRelationship {
+    /// One of `normal`, `parent` or `child`
+    #[serde(rename = "type")]
+    pub relationship_type: String,
+    pub data: Option<VaultRelationshipData>,
+}
+
+#[derive(serde::Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultRelationshipData {
+    #[serde(default)]
+    pub child_addresses: Vec<ethers::types::H160>,
+    pub parent_address: Option<ethers::types::H160>,
+}
+
 fn option_string_to_f64<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
 where
     D: serde::Deserializer<'de>,
@@ -1,4 +1,5 @@
 #![deny(unreachable_pub)]
+mod asset_registry;
 mod consts;
 mod errors;
 mod exchange;
@@ -12,6 +13,7 @@ mod signature;
 mod ws;
 
 
+pub use asset_registry::{AssetId, AssetRegistry};
 pub use consts::{
     EPSILON, LOCAL_API_URL, MAINNET_API_URL, MAINNET_WS_URL, TESTNET_API_URL, TESTNET_WS_URL,
 };
@@ -19,8 +21,13 @@ pub use errors::Error;
 pub use exchange::*;
 pub use helpers::{BaseUrl, bps_diff, shutdown_signal, truncate_float};
 pub use info::{client::*, *};
-pub use market_maker::{MarketMaker, MarketMakerInput, MarketMakerRestingOrder};
-pub use meta::{AssetMeta, Meta, SpotAssetMeta, SpotMeta};
+pub use market_maker::{
+    MarketMaker, MarketMakerBuilder, MarketMakerInput, QuoteContext, QuoteLevel, Quotes,
+    QuotingStrategy, ReferencePrice, SpreadStrategy,
+};
+pub use meta::{
+    AssetMeta, MarginTable, MarginTier, Meta, MetaAndAssetCtxs, SpotAssetMeta, SpotMeta, TokenInfo,
+};
 pub use req::NetworkType;
 pub use ws::*;
 
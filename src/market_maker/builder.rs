@@ -0,0 +1,96 @@
+use ethers::signers::LocalWallet;
+
+use crate::{
+    Error, ExchangeClient, InfoClient, MarketMaker, NetworkType, QuotingStrategy, errors::Result,
+};
+
+pub struct MarketMakerBuilder {
+    asset: String,
+    strategy: Box<dyn QuotingStrategy>,
+    network: NetworkType,
+    wallet: Option<LocalWallet>,
+    exchange_client: Option<ExchangeClient>,
+    info_client: Option<InfoClient>,
+    max_bps_diff: u16,
+}
+
+impl MarketMakerBuilder {
+    /// Resting quotes within this many bps of the strategy's price are left on the book.
+    const DEFAULT_MAX_BPS_DIFF: u16 = 2;
+
+    pub(crate) fn new(asset: &str, strategy: Box<dyn QuotingStrategy>) -> Self {
+        Self {
+            asset: asset.to_string(),
+            strategy,
+            network: NetworkType::Mainnet,
+            wallet: None,
+            exchange_client: None,
+            info_client: None,
+            max_bps_diff: Self::DEFAULT_MAX_BPS_DIFF,
+        }
+    }
+
+    /// Network of the clients built by the market maker, ignored for clients passed in.
+    pub fn network(mut self, network: NetworkType) -> Self {
+        self.network = network;
+        self
+    }
+
+    /// Wallet to build the exchange client with, when no client is passed in.
+    pub fn wallet(mut self, wallet: LocalWallet) -> Self {
+        self.wallet = Some(wallet);
+        self
+    }
+
+    pub fn exchange_client(mut self, exchange_client: ExchangeClient) -> Self {
+        self.exchange_client = Some(exchange_client);
+        self
+    }
+
+    pub fn info_client(mut self, info_client: InfoClient) -> Self {
+        self.info_client = Some(info_client);
+        self
+    }
+
+    /// Max deviation, in bps, between a resting quote and the strategy's price before the
+    /// quote is moved.
+    pub fn max_bps_diff(mut self, max_bps_diff: u16) -> Self {
+        self.max_bps_diff = max_bps_diff;
+        self
+    }
+
+    pub async fn build(self) -> Result<MarketMaker> {
+        let exchange_client = match (self.exchange_client, self.wallet) {
+            (Some(exchange_client), _) => exchange_client,
+            (None, Some(wallet)) => {
+                ExchangeClient::builder()
+                    .wallet(wallet)
+                    .network(self.network.clone())
+                    .build()
+                    .await?
+            }
+            (None, None) => {
+                return Err(Error::Wallet(
+                    "market maker needs a wallet or an exchange client".to_string(),
+                ));
+            }
+        };
+        let info_client = self
+            .info_client
+            .unwrap_or_else(|| InfoClient::builder().network(self.network).build());
+
+        Ok(MarketMaker {
+            asset: self.asset,
+            max_bps_diff: self.max_bps_diff,
+            strategy: self.strategy,
+            resting: Vec::new(),
+            cur_position: 0.0,
+            latest_mid_price: -1.0,
+            latest_book: None,
+            volatility_bps: 0.0,
+            user_address: exchange_client.account_address(),
+            info_client,
+            exchange_client,
+        })
+    }
+}
@@ -0,0 +1,502 @@
+use ethers::{signers::LocalWallet, types::H160};
+use tokio::sync::broadcast::{Receiver, error::RecvError};
+
+use crate::{
+    CancelledOrder, DesiredQuote, EPSILON, Error, ExchangeClient, ExchangeDataStatus, InfoClient,
+    L2BookData, LimitTif, MarketMakerBuilder, Message, QuoteContext, QuoteLevel, QuotingStrategy,
+    RequotedOrder, RestingQuote, SpreadStrategy, Subscription, UserData, bps_diff,
+    req::NetworkType, shutdown_signal, errors::Result,
+};
+
+#[derive(Debug)]
+pub struct MarketMakerInput {
+    pub asset: String,
+    pub target_liquidity: f64, // Amount of liquidity on both sides to target
+    pub half_spread: u16,      // Half of the spread for our market making (in BPS)
+    pub max_bps_diff: u16, // Max deviation before we cancel and put new orders on the book (in BPS)
+    pub max_absolute_position_size: f64, // Absolute value of the max position we can take on
+    pub decimals: u32,     // Decimals to round to for pricing
+    pub wallet: LocalWallet,
+    pub network: NetworkType,
+}
+
+#[derive(Debug)]
+pub struct MarketMaker {
+    pub asset: String,
+    pub max_bps_diff: u16,
+    pub strategy: Box<dyn QuotingStrategy>,
+    /// Quotes resting on the book, on both sides
+    pub resting: Vec<RestingQuote>,
+    pub cur_position: f64,
+    pub latest_mid_price: f64,
+    pub latest_book: Option<L2BookData>,
+    pub volatility_bps: f64,
+    pub info_client: InfoClient,
+    pub exchange_client: ExchangeClient,
+    pub user_address: H160,
+}
+
+impl MarketMaker {
+    /// Weight of the previous variance in the exponentially weighted volatility.
+    const VOLATILITY_DECAY: f64 = 0.94;
+
+    pub fn builder(asset: &str, strategy: impl QuotingStrategy + 'static) -> MarketMakerBuilder {
+        MarketMakerBuilder::new(asset, Box::new(strategy))
+    }
+
+    /// Market maker quoting with a [`SpreadStrategy`] on `input.network`.
+    pub async fn new(input: MarketMakerInput) -> Result<MarketMaker> {
+        let strategy = SpreadStrategy::new(
+            input.target_liquidity,
+            input.half_spread,
+            input.max_absolute_position_size,
+            input.decimals,
+        );
+
+        MarketMaker::builder(&input.asset, strategy)
+            .network(input.network)
+            .wallet(input.wallet)
+            .max_bps_diff(input.max_bps_diff)
+            .build()
+            .await
+    }
+
+    /// Quotes until [`shutdown_signal`], then cancels the resting quotes.
+    pub async fn start(&mut self) -> Result<()> {
+        self.run_until(shutdown_signal()).await
+    }
+
+    /// Quotes until `shutdown` completes or the websocket closes, then cancels the resting quotes.
+    pub async fn run_until(&mut self, shutdown: impl Future<Output = ()>) -> Result<()> {
+        // Subscribe to UserEvents for fills
+        let receiver = self
+            .info_client
+            .subscribe(Subscription::UserEvents {
+                user: self.user_address,
+            })
+            .await?;
+
+        // Subscribe to AllMids so we can market make around the mid price, and to the book for strategies using it
+        self.info_client.subscribe(Subscription::AllMids).await?;
+        self.info_client
+            .subscribe(Subscription::L2Book {
+                coin: self.asset.clone(),
+            })
+            .await?;
+
+        self.quote_until(receiver, shutdown).await
+    }
+
+    /// Handles the messages of `receiver` until `shutdown` completes or the channel closes,
+    /// then cancels the resting quotes.
+    async fn quote_until(
+        &mut self,
+        mut receiver: Receiver<Message>,
+        shutdown: impl Future<Output = ()>,
+    ) -> Result<()> {
+        let mut shutdown = std::pin::pin!(shutdown);
+        let outcome = loop {
+            tokio::select! {
+                _ = &mut shutdown => break Ok(()),
+                message = receiver.recv() => match message {
+                    Ok(message) => self.handle_message(message).await,
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Market maker skipped {skipped} messages");
+                    }
+                    Err(RecvError::Closed) => {
+                        break Err(Error::Websocket("market maker subscription closed".to_string()));
+                    }
+                },
+            }
+        };
+
+        let cancelled = self.cancel_quotes().await;
+        outcome.and(cancelled)
+    }
+
+    /// Cancels the resting quotes, e.g. on shutdown.
+    pub async fn cancel_quotes(&mut self) -> Result<()> {
+        if self.resting.is_empty() {
+            return Ok(());
+        }
+
+        let result = self
+            .exchange_client
+            .requote(&self.asset, &self.resting, &[])
+            .await;
+        self.apply_cancels(&result.cancelled);
+        result.error.map_or(Ok(()), |e| Err(e.error))
+    }
+
+    async fn handle_message(&mut self, message: Message) {
+        match message {
+            Message::AllMids(all_mids) => {
+                let all_mids = all_mids.data.mids;
+                let Some(mid) = all_mids.get(&self.asset) else {
+                    tracing::error!("could not get mid for asset {}: {all_mids:?}", self.asset);
+                    return;
+                };
+                match mid.parse::<f64>() {
+                    Ok(mid) => self.update_mid(mid),
+                    Err(e) => {
+                        tracing::error!("could not parse mid {mid} for asset {}: {e}", self.asset);
+                        return;
+                    }
+                }
+                // Check to see if we need to cancel or place any new orders
+                self.potentially_update().await;
+            }
+            Message::L2Book(book) if book.data.coin == self.asset => {
+                self.latest_book = Some(book.data);
+                self.potentially_update().await;
+            }
+            Message::User(user_events) => {
+                if let UserData::Fills(fills) = user_events.data {
+                    for fill in fills.iter().filter(|fill| fill.coin == self.asset) {
+                        let Ok(amount) = fill.sz.parse::<f64>() else {
+                            tracing::error!("could not parse fill size: {fill:?}");
+                            continue;
+                        };
+                        // Update our resting positions whenever we see a fill
+                        if fill.side.eq("B") {
+                            self.cur_position += amount;
+                            tracing::info!("Fill: bought {amount} {}", self.asset);
+                        } else {
+                            self.cur_position -= amount;
+                            tracing::info!("Fill: sold {amount} {}", self.asset);
+                        }
+                        if let Some(resting) = self
+                            .resting
+                            .iter_mut()
+                            .find(|resting| resting.oid == fill.oid)
+                        {
+                            resting.sz -= amount;
+                        }
+                        self.resting.retain(|resting| resting.sz > EPSILON);
+                    }
+                }
+                // Check to see if we need to cancel or place any new orders
+                self.potentially_update().await;
+            }
+            Message::Error(error) => tracing::error!("Websocket error: {}", error.data),
+            Message::HyperliquidError(error) => tracing::error!("Hyperliquid error: {error}"),
+            _ => {}
+        }
+    }
+
+    fn update_mid(&mut self, mid: f64) {
+        if self.latest_mid_price > 0.0 {
+            let return_bps = (mid / self.latest_mid_price).ln() * 10_000.0;
+            let variance = Self::VOLATILITY_DECAY * self.volatility_bps.powi(2)
+                + (1.0 - Self::VOLATILITY_DECAY) * return_bps.powi(2);
+            self.volatility_bps = variance.sqrt();
+        }
+        self.latest_mid_price = mid;
+    }
+
+    async fn potentially_update(&mut self) {
+        // We haven't seen the first mid price event yet
+        if self.latest_mid_price < 0.0 {
+            return;
+        }
+
+        let quotes = self.strategy.quote(&QuoteContext {
+            mid: self.latest_mid_price,
+            book: self.latest_book.as_ref(),
+            position: self.cur_position,
+            volatility_bps: self.volatility_bps,
+        });
+
+        let mut desired = sticky_levels(&self.resting, true, &quotes.bids, self.max_bps_diff);
+        desired.extend(sticky_levels(
+            &self.resting,
+            false,
+            &quotes.asks,
+            self.max_bps_diff,
+        ));
+
+        // Keeps queue priority where possible and batches the remaining cancels and orders
+        // TODO: Don't block on requotes
+        let result = self
+            .exchange_client
+            .requote(&self.asset, &self.resting, &desired)
+            .await;
+        // The requests sent before a failure still changed what is resting
+        if let Some(e) = &result.error {
+            tracing::error!("Error with requoting: {e}");
+        }
+
+        self.apply_cancels(&result.cancelled);
+        self.apply_requoted(result.requoted);
+    }
+
+    fn apply_requoted(&mut self, requoted: Vec<RequotedOrder>) {
+        for requoted in requoted {
+            let side = if requoted.quote.is_buy { "Buy" } else { "Sell" };
+            if let ExchangeDataStatus::Filled(order) = &requoted.status {
+                // Nothing rests anymore, the position is updated by the fill event
+                self.resting
+                    .retain(|resting| Some(resting.oid) != requoted.replaced_oid);
+                tracing::info!(
+                    "{side} for {} {} filled at {}",
+                    order.total_sz,
+                    self.asset,
+                    order.avg_px
+                );
+                continue;
+            }
+            let Some(oid) = requoted.resting_oid() else {
+                tracing::error!(
+                    "Unexpected status when placing order: {:?}",
+                    requoted.status
+                );
+                continue;
+            };
+            // A modified order rests under a new oid
+            self.resting
+                .retain(|resting| Some(resting.oid) != requoted.replaced_oid);
+            self.resting.push(RestingQuote {
+                oid,
+                is_buy: requoted.quote.is_buy,
+                px: requoted.quote.px,
+                sz: requoted.quote.sz,
+            });
+
+            tracing::info!(
+                "{side} for {} {} resting at {}",
+                requoted.quote.sz,
+                self.asset,
+                requoted.quote.px
+            );
+        }
+    }
+
+    fn apply_cancels(&mut self, cancelled: &[CancelledOrder]) {
+        for cancelled in cancelled {
+            // If we were unable to cancel, it means we got a fill, so wait until we receive that event to do anything
+            if !matches!(cancelled.status, ExchangeDataStatus::Success) {
+                tracing::error!("Unexpected status when cancelling: {:?}", cancelled.status);
+                continue;
+            }
+            tracing::info!("Cancelled order {} of {}", cancelled.oid, cancelled.coin);
+            self.resting.retain(|resting| resting.oid != cancelled.oid);
+        }
+    }
+}
+
+/// Desired quotes for one side of the book. The resting order at the same rank from the
+/// touch is kept in place of a level while its size matches and its price is within
+/// `max_bps_diff`, so small moves of the reference price don't churn the book.
+fn sticky_levels(
+    resting: &[RestingQuote],
+    is_buy: bool,
+    levels: &[QuoteLevel],
+    max_bps_diff: u16,
+) -> Vec<DesiredQuote> {
+    let mut side: Vec<&RestingQuote> = resting
+        .iter()
+        .filter(|resting| resting.is_buy == is_buy)
+        .collect();
+    if is_buy {
+        side.sort_by(|a, b| b.px.total_cmp(&a.px));
+    } else {
+        side.sort_by(|a, b| a.px.total_cmp(&b.px));
+    }
+
+    levels
+        .iter()
+        .enumerate()
+        .map(|(rank, level)| {
+            let level = match side.get(rank) {
+                Some(current)
+                    if (level.sz - current.sz).abs() <= EPSILON
+                        && bps_diff(level.px, current.px) <= max_bps_diff =>
+                {
+                    QuoteLevel {
+                        px: current.px,
+                        sz: current.sz,
+                    }
+                }
+                _ => *level,
+            };
+            DesiredQuote {
+                is_buy,
+                px: level.px,
+                sz: level.sz,
+                tif: LimitTif::Gtc,
+                cloid: None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        AssetRegistry, FilledOrder, RestingOrder,
+        req::test_utils::{MOCK_ENDPOINT, mock_exchange_endpoint},
+    };
+
+    async fn market_maker() -> Result<MarketMaker> {
+        let registry = AssetRegistry::new(
+            serde_json::from_str(
+                r#"{"universe":[{"name":"ETH","szDecimals":4,"maxLeverage":25}]}"#,
+            )
+            .map_err(|e| Error::JsonParse(e.to_string()))?,
+            serde_json::from_str(r#"{"universe":[],"tokens":[]}"#)
+                .map_err(|e| Error::JsonParse(e.to_string()))?,
+        );
+        let wallet: LocalWallet =
+            "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+                .parse()
+                .map_err(|e: ethers::signers::WalletError| Error::Wallet(e.to_string()))?;
+        let exchange_client = ExchangeClient::builder()
+            .wallet(wallet)
+            .network(NetworkType::Localhost)
+            .asset_registry(registry)
+            .build()
+            .await?;
+
+        MarketMaker::builder("ETH", SpreadStrategy::new(1.0, 10, 2.0, 1))
+            .exchange_client(exchange_client)
+            .info_client(
+                InfoClient::builder()
+                    .network(NetworkType::Localhost)
+                    .build(),
+            )
+            .build()
+            .await
+    }
+
+    fn resting(oid: u64, is_buy: bool, px: f64, sz: f64) -> RestingQuote {
+        RestingQuote {
+            oid,
+            is_buy,
+            px,
+            sz,
+        }
+    }
+
+    fn requoted(
+        is_buy: bool,
+        px: f64,
+        replaced_oid: Option<u64>,
+        status: ExchangeDataStatus,
+    ) -> RequotedOrder {
+        RequotedOrder {
+            quote: DesiredQuote {
+                is_buy,
+                px,
+                sz: 1.0,
+                tif: LimitTif::Gtc,
+                cloid: None,
+            },
+            replaced_oid,
+            status,
+        }
+    }
+
+    #[tokio::test]
+    async fn apply_requoted_test() -> Result<()> {
+        let mut market_maker = market_maker().await?;
+        market_maker.resting = vec![
+            resting(1, true, 1990.0, 2.0),
+            resting(2, false, 2010.0, 2.0),
+        ];
+
+        let filled = ExchangeDataStatus::Filled(FilledOrder {
+            total_sz: "1.0".to_string(),
+            avg_px: "1990.0".to_string(),
+            oid: 3,
+        });
+        let resting_status = ExchangeDataStatus::Resting(RestingOrder { oid: 4 });
+        let rejected = ExchangeDataStatus::Error("rejected".to_string());
+        market_maker.apply_requoted(vec![
+            requoted(true, 1990.0, Some(1), filled),
+            requoted(false, 2010.0, Some(2), resting_status),
+            requoted(false, 2020.0, None, rejected),
+        ]);
+
+        // Only the resting quote is tracked, the fill is left to the fill event
+        assert_eq!(market_maker.resting, vec![resting(4, false, 2010.0, 1.0)]);
+        assert_eq!(market_maker.cur_position, 0.0);
+        Ok(())
+    }
+
+    #[test]
+    fn sticky_levels_test() {
+        let resting = [
+            resting(3, false, 2003.0, 0.5),
+            resting(2, true, 1997.0, 1.0),
+            resting(4, false, 2002.0, 1.0),
+            resting(1, true, 1998.0, 1.0),
+        ];
+        let levels = |levels: &[(f64, f64)]| -> Vec<QuoteLevel> {
+            levels
+                .iter()
+                .map(|&(px, sz)| QuoteLevel { px, sz })
+                .collect()
+        };
+        let quotes = |desired: Vec<DesiredQuote>, is_buy| -> Vec<(f64, f64)> {
+            assert!(desired.iter().all(|quote| quote.is_buy == is_buy));
+            desired.iter().map(|quote| (quote.px, quote.sz)).collect()
+        };
+
+        // Matched by rank from the touch: the best bid is within 2 bps, the next one is 4 bps away
+        let bids = sticky_levels(&resting, true, &levels(&[(1998.5, 1.0), (1996.2, 1.0)]), 2);
+        assert_eq!(quotes(bids, true), vec![(1998.0, 1.0), (1996.2, 1.0)]);
+
+        // The second ask is at the same price but its size changed, the third has no match
+        let asks = sticky_levels(
+            &resting,
+            false,
+            &levels(&[(2002.3, 1.0), (2003.0, 1.0), (2004.0, 1.0)]),
+            2,
+        );
+        assert_eq!(
+            quotes(asks, false),
+            vec![(2002.0, 1.0), (2003.0, 1.0), (2004.0, 1.0)]
+        );
+    }
+
+    #[tokio::test]
+    async fn shutdown_cancels_quotes_test() -> Result<()> {
+        let _endpoint = MOCK_ENDPOINT.lock().await;
+        let server = mock_exchange_endpoint(&[
+            r#"{"status":"ok","response":{"type":"cancel","data":{"statuses":["success","success"]}}}"#,
+        ]);
+        let mut market_maker = market_maker().await?;
+        market_maker.resting = vec![
+            resting(1, true, 1990.0, 1.0),
+            resting(2, false, 2010.0, 1.0),
+        ];
+
+        let (_sender, receiver) = tokio::sync::broadcast::channel(1);
+        market_maker
+            .quote_until(receiver, std::future::ready(()))
+            .await?;
+
+        assert!(market_maker.resting.is_empty());
+        let payload: serde_json::Value = serde_json::from_str(&server.join().unwrap()[0])
+            .map_err(|e| Error::JsonParse(e.to_string()))?;
+        assert_eq!(
+            payload["action"]["cancels"],
+            serde_json::json!([{"a": 0, "o": 1}, {"a": 0, "o": 2}])
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn closed_subscription_test() -> Result<()> {
+        let mut market_maker = market_maker().await?;
+        let (sender, receiver) = tokio::sync::broadcast::channel(1);
+        drop(sender);
+
+        let outcome = market_maker
+            .quote_until(receiver, std::future::pending())
+            .await;
+        assert!(matches!(outcome, Err(Error::Websocket(_))));
+        Ok(())
+    }
+}
@@ -0,0 +1,9 @@
+mod builder;
+mod maker;
+mod strategy;
+
+pub use builder::MarketMakerBuilder;
+pub use maker::{MarketMaker, MarketMakerInput};
+pub use strategy::{
+    QuoteContext, QuoteLevel, Quotes, QuotingStrategy, ReferencePrice, SpreadStrategy,
+};
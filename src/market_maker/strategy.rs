@@ -0,0 +1,277 @@
+use crate::{EPSILON, L2BookData, truncate_float};
+
+/// Market state a [`QuotingStrategy`] prices its quotes from.
+#[derive(Debug, Clone, Copy)]
+pub struct QuoteContext<'a> {
+    /// Latest mid price of the asset
+    pub mid: f64,
+    /// Latest L2 book of the asset, `None` until the first snapshot arrives
+    pub book: Option<&'a L2BookData>,
+    /// Signed position in the asset, positive when long
+    pub position: f64,
+    /// Exponentially weighted volatility of the mid between updates, in basis points
+    pub volatility_bps: f64,
+}
+
+impl QuoteContext<'_> {
+    /// Best bid price and size of the book.
+    pub fn best_bid(&self) -> Option<QuoteLevel> {
+        self.top_of_book(0)
+    }
+
+    /// Best ask price and size of the book.
+    pub fn best_ask(&self) -> Option<QuoteLevel> {
+        self.top_of_book(1)
+    }
+
+    /// Mid of the best bid and ask, weighted towards the side with less size resting.
+    pub fn microprice(&self) -> Option<f64> {
+        let (bid, ask) = (self.best_bid()?, self.best_ask()?);
+        let total_sz = bid.sz + ask.sz;
+        if total_sz < EPSILON {
+            return None;
+        }
+        Some((bid.px * ask.sz + ask.px * bid.sz) / total_sz)
+    }
+
+    fn top_of_book(&self, side: usize) -> Option<QuoteLevel> {
+        let level = self.book?.levels.get(side)?.first()?;
+        Some(QuoteLevel {
+            px: level.px.parse().ok()?,
+            sz: level.sz.parse().ok()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuoteLevel {
+    pub px: f64,
+    pub sz: f64,
+}
+
+/// Levels to keep on the book on each side, innermost first. Empty to stay out of a side.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Quotes {
+    pub bids: Vec<QuoteLevel>,
+    pub asks: Vec<QuoteLevel>,
+}
+
+/// Prices the quotes of a [`crate::MarketMaker`], called on every mid, book and fill update.
+pub trait QuotingStrategy: std::fmt::Debug + Send {
+    fn quote(&mut self, context: &QuoteContext<'_>) -> Quotes;
+}
+
+/// Price a [`SpreadStrategy`] quotes around.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReferencePrice {
+    /// Mid from the `allMids` feed
+    #[default]
+    Mid,
+    /// Best bid for the bids and best ask for the asks
+    Bbo,
+    /// Size-weighted mid of the best bid and ask, see [`QuoteContext::microprice`]
+    Microprice,
+}
+
+/// Quotes a ladder of levels on both sides, starting `half_spread` bps away from the
+/// reference price.
+///
+/// Level sizes are capped so that a fill of the whole side never takes the position beyond
+/// `max_absolute_position_size`. With `inventory_skew`, both sides are shifted against the
+/// position, by up to `inventory_skew` bps at the maximum position, so that the side reducing
+/// it gets filled first. Without a book, the reference falls back to the mid.
+#[derive(Debug, Clone)]
+pub struct SpreadStrategy {
+    /// Size of each level on both sides, innermost first
+    pub ladder: Vec<f64>,
+    /// Half of the spread, in bps
+    pub half_spread: u16,
+    /// Distance between consecutive levels, in bps
+    pub level_spacing: u16,
+    pub max_absolute_position_size: f64,
+    /// Price shift at the maximum position, in bps
+    pub inventory_skew: u16,
+    pub reference: ReferencePrice,
+    /// Decimals to round prices to
+    pub decimals: u32,
+}
+
+impl SpreadStrategy {
+    /// One level of `target_liquidity` on each side of the mid, without inventory skew.
+    pub fn new(
+        target_liquidity: f64,
+        half_spread: u16,
+        max_absolute_position_size: f64,
+        decimals: u32,
+    ) -> Self {
+        Self {
+            ladder: vec![target_liquidity],
+            half_spread,
+            level_spacing: 0,
+            max_absolute_position_size,
+            inventory_skew: 0,
+            reference: ReferencePrice::Mid,
+            decimals,
+        }
+    }
+
+    /// Reference prices for the bids and the asks.
+    fn reference_prices(&self, context: &QuoteContext<'_>) -> (f64, f64) {
+        match self.reference {
+            ReferencePrice::Mid => (context.mid, context.mid),
+            ReferencePrice::Bbo => (
+                context.best_bid().map_or(context.mid, |bid| bid.px),
+                context.best_ask().map_or(context.mid, |ask| ask.px),
+            ),
+            ReferencePrice::Microprice => {
+                let microprice = context.microprice().unwrap_or(context.mid);
+                (microprice, microprice)
+            }
+        }
+    }
+
+    /// Level sizes of one side, stopping once `capacity` is used up.
+    fn level_sizes(&self, mut capacity: f64) -> Vec<f64> {
+        let mut sizes = Vec::new();
+        for size in &self.ladder {
+            let sz = size.min(capacity);
+            if sz <= EPSILON {
+                break;
+            }
+            capacity -= sz;
+            sizes.push(sz);
+        }
+        sizes
+    }
+}
+
+impl QuotingStrategy for SpreadStrategy {
+    fn quote(&mut self, context: &QuoteContext<'_>) -> Quotes {
+        let inventory = if self.max_absolute_position_size > EPSILON {
+            (context.position / self.max_absolute_position_size).clamp(-1.0, 1.0)
+        } else {
+            0.0
+        };
+        let skew = 1.0 - inventory * self.inventory_skew as f64 / 10000.0;
+        let (bid_reference, ask_reference) = self.reference_prices(context);
+        let (bid_reference, ask_reference) = (bid_reference * skew, ask_reference * skew);
+
+        let bid_sizes = self.level_sizes(self.max_absolute_position_size - context.position);
+        let ask_sizes = self.level_sizes(self.max_absolute_position_size + context.position);
+        let offset = |level: usize| {
+            (self.half_spread as f64 + (level as f64) * self.level_spacing as f64) / 10000.0
+        };
+
+        let mut bids: Vec<QuoteLevel> = bid_sizes
+            .into_iter()
+            .enumerate()
+            .map(|(level, sz)| QuoteLevel {
+                px: truncate_float(
+                    bid_reference - bid_reference * offset(level),
+                    self.decimals,
+                    true,
+                ),
+                sz,
+            })
+            .collect();
+        let mut asks: Vec<QuoteLevel> = ask_sizes
+            .into_iter()
+            .enumerate()
+            .map(|(level, sz)| QuoteLevel {
+                px: truncate_float(
+                    ask_reference + ask_reference * offset(level),
+                    self.decimals,
+                    false,
+                ),
+                sz,
+            })
+            .collect();
+
+        // Rounding optimistically to make our market tighter might cause a weird edge case, so account for that
+        if let (Some(bid), Some(ask)) = (bids.first_mut(), asks.first_mut())
+            && bid.px >= ask.px - EPSILON
+        {
+            bid.px = truncate_float(bid.px, self.decimals, false);
+            ask.px = truncate_float(ask.px, self.decimals, true);
+        }
+
+        Quotes { bids, asks }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(position: f64, book: Option<&L2BookData>) -> QuoteContext<'_> {
+        QuoteContext {
+            mid: 2000.0,
+            book,
+            position,
+            volatility_bps: 0.0,
+        }
+    }
+
+    #[test]
+    fn spread_strategy_test() {
+        let mut strategy = SpreadStrategy::new(0.25, 10, 0.5, 1);
+        let level = |px, sz| QuoteLevel { px, sz };
+
+        let quotes = strategy.quote(&context(0.0, None));
+        assert_eq!(quotes.bids, vec![level(1998.1, 0.25)]);
+        assert_eq!(quotes.asks, vec![level(2002.0, 0.25)]);
+
+        let quotes = strategy.quote(&context(0.4, None));
+        assert!((quotes.bids[0].sz - 0.1).abs() < EPSILON);
+        assert_eq!(quotes.asks[0].sz, 0.25);
+
+        assert!(strategy.quote(&context(0.5, None)).bids.is_empty());
+    }
+
+    #[test]
+    fn ladder_and_skew_test() {
+        let mut strategy = SpreadStrategy {
+            ladder: vec![0.1, 0.2, 0.3],
+            level_spacing: 5,
+            inventory_skew: 20,
+            ..SpreadStrategy::new(0.0, 10, 0.5, 1)
+        };
+        let prices = |levels: &[QuoteLevel]| -> Vec<f64> { levels.iter().map(|l| l.px).collect() };
+
+        let quotes = strategy.quote(&context(0.0, None));
+        assert_eq!(prices(&quotes.bids), vec![1998.1, 1997.1, 1996.1]);
+        assert_eq!(prices(&quotes.asks), vec![2002.0, 2003.0, 2004.0]);
+
+        // Long 0.25 of 0.5: shifted down by 10 bps, and only 0.25 more can be bought
+        let quotes = strategy.quote(&context(0.25, None));
+        assert_eq!(prices(&quotes.bids), vec![1996.1, 1995.1]);
+        assert_eq!(quotes.bids[1].sz, 0.15);
+        assert_eq!(prices(&quotes.asks), vec![1999.9, 2000.9, 2001.9]);
+    }
+
+    #[test]
+    fn book_reference_test() {
+        let book: L2BookData = serde_json::from_str(
+            r#"{"coin":"ETH","time":0,"levels":[
+                [{"px":"1999.0","sz":"3.0","n":1}],
+                [{"px":"2003.0","sz":"1.0","n":1}]
+            ]}"#,
+        )
+        .unwrap();
+        let context = context(0.0, Some(&book));
+        assert_eq!(context.microprice(), Some(2002.0));
+
+        let mut strategy = SpreadStrategy {
+            reference: ReferencePrice::Bbo,
+            ..SpreadStrategy::new(0.25, 10, 0.5, 1)
+        };
+        let quotes = strategy.quote(&context);
+        assert_eq!(quotes.bids[0].px, 1997.1);
+        assert_eq!(quotes.asks[0].px, 2005.0);
+
+        strategy.reference = ReferencePrice::Microprice;
+        let quotes = strategy.quote(&context);
+        assert_eq!(quotes.bids[0].px, 2000.0);
+        assert_eq!(quotes.asks[0].px, 2004.0);
+    }
+}
@@ -1,46 +1,72 @@
+use crate::PerpsAssetCtx;
+
 #[derive(serde::Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct Meta {
     pub universe: Vec<AssetMeta>,
+    /// Margin tables as `(id, table)` pairs, referenced by [`AssetMeta::margin_table_id`]
+    #[serde(default)]
+    pub margin_tables: Vec<(u32, MarginTable)>,
+    /// Token index of the collateral of the perp dex
+    #[serde(default)]
+    pub collateral_token: Option<usize>,
 }
 
-#[derive(serde::Deserialize, Debug, Clone)]
-pub struct SpotMeta {
-    pub universe: Vec<SpotAssetMeta>,
-    pub tokens: Vec<TokenInfo>,
-}
-
-impl SpotMeta {
-    pub fn add_to_coin_to_asset_map(&self, coin_to_asset: &mut scc::HashMap<String, u32>) {
-        let index_to_name: scc::HashMap<usize, &str> = self
-            .tokens
+impl Meta {
+    pub fn margin_table(&self, id: u32) -> Option<&MarginTable> {
+        self.margin_tables
             .iter()
-            .map(|info| (info.index, info.name.as_str()))
-            .collect();
+            .find(|(table_id, _)| *table_id == id)
+            .map(|(_, table)| table)
+    }
+}
 
-        for asset in self.universe.iter() {
-            let spot_ind: u32 = 10000 + asset.index as u32;
-            let name_to_ind = (asset.name.clone(), spot_ind);
+#[derive(serde::Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginTable {
+    pub description: String,
+    pub margin_tiers: Vec<MarginTier>,
+}
 
-            let Some(token_1_name) = index_to_name.read(&asset.tokens[0], |_, name| *name) else {
-                continue;
-            };
+#[derive(serde::Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginTier {
+    /// Position notional from which the tier applies
+    pub lower_bound: String,
+    pub max_leverage: u32,
+}
 
-            let Some(token_2_name) = index_to_name.read(&asset.tokens[1], |_, name| *name) else {
-                continue;
-            };
+/// Perp metadata and asset contexts, the contexts being in the same order as the universe.
+#[derive(serde::Deserialize, Debug, Clone)]
+#[serde(from = "(Meta, Vec<PerpsAssetCtx>)")]
+pub struct MetaAndAssetCtxs {
+    pub meta: Meta,
+    pub asset_ctxs: Vec<PerpsAssetCtx>,
+}
 
-            let key1 = format!("{token_1_name}/{token_2_name}");
-            let val1 = spot_ind;
+impl From<(Meta, Vec<PerpsAssetCtx>)> for MetaAndAssetCtxs {
+    fn from((meta, asset_ctxs): (Meta, Vec<PerpsAssetCtx>)) -> Self {
+        Self { meta, asset_ctxs }
+    }
+}
 
-            let key2 = name_to_ind.0;
-            let val2 = name_to_ind.1;
+impl MetaAndAssetCtxs {
+    /// Each asset of the universe with its context.
+    pub fn assets(&self) -> impl Iterator<Item = (&AssetMeta, &PerpsAssetCtx)> {
+        self.meta.universe.iter().zip(self.asset_ctxs.iter())
+    }
 
-            coin_to_asset.insert(key1, val1).ok();
-            coin_to_asset.insert(key2, val2).ok();
-        }
+    pub fn asset(&self, coin: &str) -> Option<(&AssetMeta, &PerpsAssetCtx)> {
+        self.assets().find(|(meta, _)| meta.name == coin)
     }
 }
 
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct SpotMeta {
+    pub universe: Vec<SpotAssetMeta>,
+    pub tokens: Vec<TokenInfo>,
+}
+
 #[derive(serde::Deserialize, Debug, Clone)]
 #[serde(untagged)]
 pub enum SpotMetaAndAssetCtxs {
@@ -64,6 +90,13 @@ pub struct SpotAssetContext {
 pub struct AssetMeta {
     pub name: String,
     pub sz_decimals: u32,
+    pub max_leverage: u32,
+    #[serde(default)]
+    pub margin_table_id: Option<u32>,
+    #[serde(default)]
+    pub only_isolated: bool,
+    #[serde(default)]
+    pub is_delisted: bool,
 }
 
 #[derive(serde::Deserialize, Debug, Clone)]
@@ -85,3 +118,45 @@ pub struct TokenInfo {
     pub token_id: ethers::abi::ethereum_types::H128,
     pub is_canonical: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn meta_and_asset_ctxs_test() {
+        let response: MetaAndAssetCtxs = serde_json::from_str(
+            r#"[
+                {
+                    "universe":[
+                        {"name":"BTC","szDecimals":5,"maxLeverage":40,"marginTableId":56},
+                        {"name":"MATIC","szDecimals":1,"maxLeverage":20,"marginTableId":20,"isDelisted":true}
+                    ],
+                    "marginTables":[[56,{"description":"tiered 40x","marginTiers":[
+                        {"lowerBound":"0.0","maxLeverage":40},
+                        {"lowerBound":"150000000.0","maxLeverage":20}
+                    ]}]],
+                    "collateralToken":0
+                },
+                [
+                    {"funding":"0.0000125","openInterest":"10000.0","prevDayPx":"100000.0","dayNtlVlm":"1.0","premium":"0.0001","oraclePx":"100000.0","markPx":"100010.0","midPx":"100005.0","impactPxs":["100000.0","100010.0"],"dayBaseVlm":"0.1"},
+                    {"funding":"0.0","openInterest":"0.0","prevDayPx":"0.37","dayNtlVlm":"0.0","premium":null,"oraclePx":"0.37","markPx":"0.37","midPx":null,"impactPxs":null,"dayBaseVlm":"0.0"}
+                ]
+            ]"#,
+        )
+        .unwrap();
+
+        let (btc, btc_ctx) = response.asset("BTC").unwrap();
+        assert_eq!(btc.max_leverage, 40);
+        assert_eq!(btc_ctx.premium.as_deref(), Some("0.0001"));
+        let table = response
+            .meta
+            .margin_table(btc.margin_table_id.unwrap())
+            .unwrap();
+        assert_eq!(table.margin_tiers[1].max_leverage, 20);
+
+        let (matic, matic_ctx) = response.asset("MATIC").unwrap();
+        assert!(matic.is_delisted && !matic.only_isolated);
+        assert!(matic_ctx.impact_pxs.is_none());
+    }
+}
@@ -107,3 +107,51 @@ impl From<Endpoint> for &str {
         }
     }
 }
+
+#[cfg(test)]
+pub(crate) mod test_utils {
+    /// Held by the tests serving the localhost endpoint, as they all bind the same port.
+    pub(crate) static MOCK_ENDPOINT: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+    /// Serves one request per response on the localhost endpoint, in order, and returns
+    /// their bodies.
+    pub(crate) fn mock_exchange_endpoint(
+        responses: &'static [&'static str],
+    ) -> std::thread::JoinHandle<Vec<String>> {
+        use std::io::{BufRead, BufReader, Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:3001").unwrap();
+        std::thread::spawn(move || {
+            let mut bodies = Vec::new();
+            for response in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line == "\r\n" {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':')
+                        && name.eq_ignore_ascii_case("content-length")
+                    {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+
+                // Closing the connection makes the client open a new one for the next request
+                write!(
+                    reader.get_mut(),
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response}",
+                    response.len()
+                )
+                .unwrap();
+                bodies.push(String::from_utf8(body).unwrap());
+            }
+            bodies
+        })
+    }
+}
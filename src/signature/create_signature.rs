@@ -1,35 +1,50 @@
 use ethers::{
     core::k256::{Secp256k1, elliptic_curve::FieldBytes},
     signers::LocalWallet,
-    types::{H256, Signature, U256, transaction::eip712::Eip712},
+    types::{H160, H256, Signature, U256, transaction::eip712::Eip712},
 };
 
 use crate::{Error, proxy_digest::Sha256Proxy, signature::agent::l1, errors::Result};
 
+#[cfg(test)]
 pub(crate) fn sign_l1_action(
     wallet: &LocalWallet,
     connection_id: H256,
     is_mainnet: bool,
 ) -> Result<Signature> {
-    let source = if is_mainnet { "a" } else { "b" }.to_string();
-    sign_typed_data(
-        &l1::Agent {
-            source,
-            connection_id,
-        },
-        wallet,
-    )
+    sign_hash(l1_action_hash(connection_id, is_mainnet)?, wallet)
 }
 
+#[cfg(test)]
 pub(crate) fn sign_typed_data<T: Eip712>(payload: &T, wallet: &LocalWallet) -> Result<Signature> {
+    sign_hash(typed_data_hash(payload)?, wallet)
+}
+
+/// EIP-712 hash of the phantom agent signed for an L1 action.
+pub(crate) fn l1_action_hash(connection_id: H256, is_mainnet: bool) -> Result<H256> {
+    let source = if is_mainnet { "a" } else { "b" }.to_string();
+    typed_data_hash(&l1::Agent {
+        source,
+        connection_id,
+    })
+}
+
+pub(crate) fn typed_data_hash<T: Eip712>(payload: &T) -> Result<H256> {
     let encoded = payload
         .encode_eip712()
         .map_err(|e| Error::Eip712(e.to_string()))?;
 
-    sign_hash(H256::from(encoded), wallet)
+    Ok(H256::from(encoded))
+}
+
+/// Address of the wallet that signed `hash`.
+pub(crate) fn recover_signer(hash: H256, signature: &Signature) -> Result<H160> {
+    signature
+        .recover(hash)
+        .map_err(|e| Error::SignatureFailure(e.to_string()))
 }
 
-fn sign_hash(hash: H256, wallet: &LocalWallet) -> Result<Signature> {
+pub(crate) fn sign_hash(hash: H256, wallet: &LocalWallet) -> Result<Signature> {
     let (sig, rec_id) = wallet
         .signer()
         .sign_digest_recoverable(Sha256Proxy::from(hash))
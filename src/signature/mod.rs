@@ -1,4 +1,6 @@
 pub(crate) mod agent;
 mod create_signature;
 
-pub(crate) use create_signature::{sign_l1_action, sign_typed_data};
+#[cfg(test)]
+pub(crate) use create_signature::sign_l1_action;
+pub(crate) use create_signature::{l1_action_hash, recover_signer, sign_hash, typed_data_hash};
@@ -27,6 +27,16 @@ pub struct UserFills {
     pub data: UserFillsData,
 }
 
+#[derive(Deserialize, Clone, Debug)]
+pub struct UserTwapSliceFills {
+    pub data: UserTwapSliceFillsData,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct UserTwapHistory {
+    pub data: UserTwapHistoryData,
+}
+
 #[derive(Deserialize, Clone, Debug)]
 pub struct Candle {
     pub data: CandleData,
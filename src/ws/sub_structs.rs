@@ -57,6 +57,60 @@ pub struct UserFillsData {
     pub fills: Vec<TradeInfo>,
 }
 
+#[derive(serde::Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TwapSliceFill {
+    pub fill: TradeInfo,
+    pub twap_id: u64,
+}
+
+#[derive(serde::Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UserTwapSliceFillsData {
+    pub is_snapshot: Option<bool>,
+    pub user: ethers::types::H160,
+    pub twap_slice_fills: Vec<TwapSliceFill>,
+}
+
+#[derive(serde::Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TwapState {
+    pub coin: String,
+    pub user: ethers::types::H160,
+    pub side: String,
+    pub sz: String,
+    pub executed_sz: String,
+    pub executed_ntl: String,
+    pub minutes: u32,
+    pub reduce_only: bool,
+    pub randomize: bool,
+    pub timestamp: u64,
+}
+
+#[derive(serde::Deserialize, Clone, Debug)]
+pub struct TwapHistoryStatus {
+    /// One of `activated`, `terminated`, `finished` or `error`
+    pub status: String,
+    pub description: Option<String>,
+}
+
+#[derive(serde::Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TwapHistory {
+    pub time: u64,
+    pub state: TwapState,
+    pub status: TwapHistoryStatus,
+    pub twap_id: Option<u64>,
+}
+
+#[derive(serde::Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UserTwapHistoryData {
+    pub is_snapshot: Option<bool>,
+    pub user: ethers::types::H160,
+    pub history: Vec<TwapHistory>,
+}
+
 #[derive(serde::Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub enum UserData {
@@ -310,6 +364,13 @@ pub struct PerpsAssetCtx {
     pub funding: String,
     pub open_interest: String,
     pub oracle_px: String,
+    #[serde(default)]
+    pub premium: Option<String>,
+    /// Impact bid and ask prices
+    #[serde(default)]
+    pub impact_pxs: Option<Vec<String>>,
+    #[serde(default)]
+    pub day_base_vlm: Option<String>,
 }
 
 #[derive(serde::Deserialize, Clone, Debug)]
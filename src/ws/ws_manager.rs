@@ -66,6 +66,12 @@ pub enum Subscription {
     UserNonFundingLedgerUpdates {
         user: ethers::types::H160,
     },
+    UserTwapSliceFills {
+        user: ethers::types::H160,
+    },
+    UserTwapHistory {
+        user: ethers::types::H160,
+    },
     ActiveAssetCtx {
         coin: String,
     },
@@ -115,6 +121,8 @@ pub enum Message {
     OrderUpdates(crate::ws::message_types::OrderUpdates),
     UserFundings(crate::UserFundings),
     UserNonFundingLedgerUpdates(crate::UserNonFundingLedgerUpdates),
+    UserTwapSliceFills(crate::ws::message_types::UserTwapSliceFills),
+    UserTwapHistory(crate::ws::message_types::UserTwapHistory),
     Notification(crate::Notification),
     WebData2(crate::WebData2),
     ActiveAssetCtx(crate::ActiveAssetCtx),